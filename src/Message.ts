@@ -6,4 +6,18 @@ export type Message =
     { t: "canContainOnly", num: number, row: number, col: number } |
     { t: "numberOnlyFitsInRow", num: number, row: number } |
     { t: "numberOnlyFitsInCol", num: number, col: number } |
-    { t: "numberOnlyFitsInBlock", num: number, row: number, col: number };
\ No newline at end of file
+    { t: "numberOnlyFitsInBlock", num: number, row: number, col: number } |
+    { t: "numberOnlyFitsInRegion", num: number, region: number } |
+    { t: "numberOnlyFitsInDiagonal", num: number, diagonal: number } |
+    { t: "numberOnlyFitsInWindow", num: number, row: number, col: number } |
+    { t: "cageCombinations", sum: number, row: number, col: number, combinations: number[][] } |
+    { t: "thermometerIncreases", row: number, col: number } |
+    { t: "arrowSum", row: number, col: number } |
+    { t: "palindromeMirrors", row: number, col: number } |
+    { t: "pairClue", kind: "white" | "black" | "x" | "v" | "greaterThan", row: number, col: number, row2: number, col2: number } |
+    { t: "rowSandwich", sum: number, row: number } |
+    { t: "colSandwich", sum: number, col: number } |
+    { t: "littleKiller", sum: number, row: number, col: number } |
+    { t: "custom", text: string } |
+    { t: "xWingInRows", num: number, row: number, row2: number, col: number, col2: number } |
+    { t: "xWingInCols", num: number, row: number, row2: number, col: number, col2: number };
\ No newline at end of file
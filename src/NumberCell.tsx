@@ -1,15 +1,22 @@
 import React from "react";
+import {CellOrigin} from "./board";
 
 type Props = {
     index: number;
     value: number | null;
+    origin: CellOrigin;
+    // Givens and the boards of the solution steps can't be changed
+    readonly: boolean;
     setNum: (val: number | null) => void;
     max: number;
 }
 
 export const NumberCell: React.FC<Props> = (props) => {
     return (
-        <input tabIndex={props.index} className={"sudoku-cell"} value={(props.value ?? "") + ""} onChange={e => {
+        <input tabIndex={props.index} className={"sudoku-cell " + (props.value !== null ? props.origin : "")}
+               readOnly={props.readonly} value={(props.value ?? "") + ""} onChange={e => {
+            if (props.readonly)
+                return;
             let num = Number.parseInt(e.currentTarget.value);
             if (isNaN(num) || num < 1)
                 props.setNum(null);
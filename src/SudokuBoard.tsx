@@ -17,7 +17,7 @@ export const SudokuBoard: React.FC<Props> = (props) => {
     let rows = [];
     
     function updateBoard(row: number, col: number, value: number | null) {
-        props.setBoard(board.copy().set(row, col, value));
+        props.setBoard(board.copy().set(row, col, value, "user"));
     }
     
     let firstRow = [
@@ -62,8 +62,9 @@ export const SudokuBoard: React.FC<Props> = (props) => {
                     (c % blockSize === 0 ? " block-col-start " : "")}>
                     <CellBase highlighted={props.highlightRow === r || props.highlightCol === c ||
                         (props.highlightBlock !== null && props.highlightBlock[0] === blockRow && props.highlightBlock[1] === blockCol)}>
-                        <NumberCell index={2 + index++} value={board.get(r, c)} max={size}
-                                    setNum={(value) => props.readonly || updateBoard(r, c, value)}></NumberCell>
+                        <NumberCell index={2 + index++} value={board.get(r, c)} origin={board.getOrigin(r, c)} max={size}
+                                    readonly={props.readonly || board.isGiven(r, c)}
+                                    setNum={(value) => updateBoard(r, c, value)}></NumberCell>
                     </CellBase>
                 </td>
             );
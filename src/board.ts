@@ -1,9 +1,16 @@
 
+// Where the number in a cell came from. Givens are the clues of the puzzle and can't be changed
+export type CellOrigin = "given" | "user" | "solver";
+
+// Suffix of the numbers of each origin in a literal, like "5+" for a number placed by the user
+const originSuffixes: Record<CellOrigin, string> = {given: "", user: "+", solver: "*"};
+
 export class Board {
     readonly size: number;
     
     public constructor(public blockSize: number,
-                       public cells: (number | null)[]) {
+                       public cells: (number | null)[],
+                       public origins: CellOrigin[] = cells.map((): CellOrigin => "given")) {
         this.size = blockSize * blockSize;
     }
     
@@ -12,36 +19,49 @@ export class Board {
         return new Board(blockSize, new Array(blockSize * blockSize * blockSize * blockSize).fill(null))
     }
     
-    // A literal a continuous string representation of the board, in the format "1 2 3 _ _ 6 7 8 _"
-    // that can contain new lines
+    // A literal a continuous string representation of the board, in the format "1 2+ 3* _ _ 6 7 8 _"
+    // that can contain new lines. Numbers placed by the user end with "+" and by the solver with "*"
     public static fromLiteral(literal: string, blockSize: number) {
-        let array = literal
+        let tokens = literal
             .replace("\n", " ")
             .split(" ")
-            .filter(o => o.length !== 0)
+            .filter(o => o.length !== 0);
+        let array = tokens
             .map(o => Number.parseInt(o))
             .map(o => Number.isNaN(o) ? null : o);
+        let origins = tokens
+            .map((o): CellOrigin => o.endsWith("+") ? "user" : o.endsWith("*") ? "solver" : "given");
         
-        return new Board(blockSize, array);
+        return new Board(blockSize, array, origins);
     }
     
     public get(row: number, col: number) {
         return this.cells[row * this.size + col];
     }
     
-    public set(row: number, col: number, value: number | null) {
+    public getOrigin(row: number, col: number) {
+        return this.origins[row * this.size + col];
+    }
+    
+    public isGiven(row: number, col: number) {
+        return this.get(row, col) !== null && this.getOrigin(row, col) === "given";
+    }
+    
+    public set(row: number, col: number, value: number | null, origin: CellOrigin = "given") {
         this.cells[row * this.size + col] = value;
+        this.origins[row * this.size + col] = origin;
         return this;
     }
     
     // Deep copy of the object
     public copy() {
-        return new Board(this.blockSize, [...this.cells]);
+        return new Board(this.blockSize, [...this.cells], [...this.origins]);
     }
     
-    // Converts the board to the format "1 2 3 _ _ 6 7 8 _"
+    // Converts the board to the format "1 2+ 3* _ _ 6 7 8 _"
     public toLiteral() {
-        return this.cells.reduce((acc, value) => acc + (value ?? "_") + " ", "");
+        return this.cells.reduce((acc, value, index) =>
+            acc + (value === null ? "_" : value + originSuffixes[this.origins[index]]) + " ", "");
     }
 }
 
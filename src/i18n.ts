@@ -44,6 +44,20 @@ i18next
                     numberOnlyFitsInRow: "Number {{num}} can only be placed in one cell in row {{row}}",
                     numberOnlyFitsInCol: "Number {{num}} can only be placed in one cell in col {{col}}",
                     numberOnlyFitsInBlock: "Number {{num}} can only be placed in one cell in block {{row}},{{col}}",
+                    numberOnlyFitsInRegion: "Number {{num}} can only be placed in one cell in region {{region}}",
+                    numberOnlyFitsInDiagonal: "Number {{num}} can only be placed in one cell in diagonal {{diagonal}}",
+                    numberOnlyFitsInWindow: "Number {{num}} can only be placed in one cell in window {{row}},{{col}}",
+                    cageCombinations: "Cage {{sum}} at {{row}},{{col}} can only contain one of {{combinations}}",
+                    thermometerIncreases: "Numbers must increase along the thermometer at {{row}},{{col}}",
+                    arrowSum: "The circle at {{row}},{{col}} must contain the sum of its arrow",
+                    palindromeMirrors: "The palindrome at {{row}},{{col}} must read the same in both directions",
+                    pairClue: "Cells {{row}},{{col}} and {{row2}},{{col2}} must follow their {{kind}} clue",
+                    rowSandwich: "Numbers between 1 and the biggest number in row {{row}} must add up to {{sum}}",
+                    colSandwich: "Numbers between 1 and the biggest number in col {{col}} must add up to {{sum}}",
+                    littleKiller: "Numbers along the diagonal from {{row}},{{col}} must add up to {{sum}}",
+                    custom: "{{text}}",
+                    xWingInRows: "Number {{num}} only fits in cols {{col}} and {{col2}} of rows {{row}} and {{row2}}, so it can't be anywhere else in those cols",
+                    xWingInCols: "Number {{num}} only fits in rows {{row}} and {{row2}} of cols {{col}} and {{col2}}, so it can't be anywhere else in those rows",
                     generate: "Generate board",
                     solve: "Solution",
                 }
@@ -76,6 +90,20 @@ i18next
                     numberOnlyFitsInRow: "O número {{num}} apenas pode ser colocado em uma casa na linha {{row}}",
                     numberOnlyFitsInCol: "O número {{num}} apenas pode ser colocado em uma casa na coluna {{col}}",
                     numberOnlyFitsInBlock: "O número {{num}} apenas pode ser colocado em uma casa no bloco {{row}},{{col}}",
+                    numberOnlyFitsInRegion: "O número {{num}} apenas pode ser colocado em uma casa na região {{region}}",
+                    numberOnlyFitsInDiagonal: "O número {{num}} apenas pode ser colocado em uma casa na diagonal {{diagonal}}",
+                    numberOnlyFitsInWindow: "O número {{num}} apenas pode ser colocado em uma casa na janela {{row}},{{col}}",
+                    cageCombinations: "A gaiola {{sum}} em {{row}},{{col}} apenas pode conter uma de {{combinations}}",
+                    thermometerIncreases: "Os números devem aumentar ao longo do termômetro em {{row}},{{col}}",
+                    arrowSum: "O círculo em {{row}},{{col}} deve conter a soma da sua seta",
+                    palindromeMirrors: "O palíndromo em {{row}},{{col}} deve ser igual nos dois sentidos",
+                    pairClue: "As casas {{row}},{{col}} e {{row2}},{{col2}} devem seguir a sua pista {{kind}}",
+                    rowSandwich: "Os números entre o 1 e o maior número da linha {{row}} devem somar {{sum}}",
+                    colSandwich: "Os números entre o 1 e o maior número da coluna {{col}} devem somar {{sum}}",
+                    littleKiller: "Os números ao longo da diagonal a partir de {{row}},{{col}} devem somar {{sum}}",
+                    custom: "{{text}}",
+                    xWingInRows: "O número {{num}} apenas cabe nas colunas {{col}} e {{col2}} das linhas {{row}} e {{row2}}, então não pode estar em outro lugar dessas colunas",
+                    xWingInCols: "O número {{num}} apenas cabe nas linhas {{row}} e {{row2}} das colunas {{col}} e {{col2}}, então não pode estar em outro lugar dessas linhas",
                     generate: "Gerar tabuleiro",
                     solve: "Solução",
                 }
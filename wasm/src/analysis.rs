@@ -0,0 +1,289 @@
+use serde::Serialize;
+use crate::generator::{grade, redundant_givens, Difficulty, Symmetry};
+use crate::number_options::NumberOptions;
+use crate::solve_report::Technique;
+use crate::sudoku_board::{CellOrigin, SudokuBoard};
+use crate::sudoku_solver::{SudokuSolver, DEFAULT_MAX_TECHNIQUE};
+
+/// Information about a puzzle, like the ones shown with published puzzles.
+/// Serialized as {"clues", "rowClues", "colClues", "blockClues", "missingDigits", "symmetries",
+/// "unique", "minimal", "difficulty", "hardestTechnique"}
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PuzzleInfo {
+    /// Number of givens
+    pub clues: usize,
+    pub row_clues: Vec<usize>,
+    pub col_clues: Vec<usize>,
+    /// Givens in each block, or region of a Jigsaw Sudoku, by id
+    pub block_clues: Vec<usize>,
+    /// Numbers that don't appear in any given
+    pub missing_digits: Vec<u8>,
+    /// Every symmetry of the cells with givens, besides [Symmetry::None]
+    pub symmetries: Vec<Symmetry>,
+    pub unique: bool,
+    /// Whether removing any given would allow more solutions
+    pub minimal: bool,
+    /// None if the puzzle doesn't have a unique solution
+    pub difficulty: Option<Difficulty>,
+    /// Hardest technique needed to solve the puzzle without guessing, or None if it needs guessing
+    pub hardest_technique: Option<Technique>,
+    /// Size of the smallest backdoors, see [backdoors]. 0 if singles solve the puzzle, and None if
+    /// it doesn't have a unique solution or needs more than [MAX_BACKDOOR_SIZE] cells
+    pub backdoor_size: Option<usize>,
+}
+
+/// Biggest backdoor searched by [analyze] and the wasm interface. Every pair of empty cells is
+/// tried, which is fast enough for 9x9 boards
+pub const MAX_BACKDOOR_SIZE: usize = 2;
+
+/// Number that lets the solver finish the puzzle without backtracking when guessed in the cell.
+/// Serialized as {"num", "row", "col"}, with a 0-based row and col
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct KeyGuess {
+    pub num: u8,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Backdoors and key guesses of a puzzle. Serialized as {"backdoors", "keyGuesses"}, where every
+/// backdoor is a list of [row, col], and "backdoors" is null if the puzzle doesn't have a unique
+/// solution
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackdoorReport {
+    pub backdoors: Option<Vec<Vec<[usize; 2]>>>,
+    pub key_guesses: Vec<KeyGuess>,
+}
+
+/// Count the givens of the puzzle in each house, find its symmetries, and check its solutions and
+/// difficulty. Numbers placed by the player or the solver are ignored
+pub fn analyze<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> PuzzleInfo {
+    let board = &board.givens();
+    let mut row_clues = vec![0; SIZE];
+    let mut col_clues = vec![0; SIZE];
+    let mut block_clues = vec![0; SIZE];
+    let mut used = NumberOptions::<SIZE>::default();
+
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            let Some(num) = board.get_number(row, col) else { continue; };
+            row_clues[row] += 1;
+            col_clues[col] += 1;
+            block_clues[board.rules().region_of(row, col)] += 1;
+            used.add_number(num);
+        }
+    }
+
+    let symmetries = [Symmetry::Rotate180, Symmetry::Rotate90, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal]
+        .into_iter()
+        .filter(|symmetry| (0..SIZE * SIZE).all(|index| {
+            let given = board.get_number(index / SIZE, index % SIZE).is_some();
+            symmetry.orbit::<SIZE>(index / SIZE, index % SIZE).iter().all(|&[r, c]| board.get_number(r, c).is_some() == given)
+        }))
+        .collect();
+
+    let unique = SudokuSolver::new(0).count_solutions(board, 2) == 1;
+    let (difficulty, hardest_technique) = if unique { grade(board) } else { (Difficulty::Expert, None) };
+
+    PuzzleInfo {
+        clues: row_clues.iter().sum(),
+        row_clues,
+        col_clues,
+        block_clues,
+        missing_digits: (!used).as_vec(),
+        symmetries,
+        unique,
+        minimal: unique && redundant_givens(board).is_empty(),
+        difficulty: unique.then_some(difficulty),
+        hardest_technique,
+        backdoor_size: backdoors(board, MAX_BACKDOOR_SIZE).and_then(|o| o.first().map(|o| o.len())),
+    }
+}
+
+/// Find the smallest sets of at most max_size empty cells which, filled with their solution, let
+/// the singles solve the puzzle. Hard puzzles have no small backdoors. Only the cells the singles
+/// can't fill are tried, since giving any other one doesn't help them. Return a single empty set if
+/// the singles don't need help, no sets if every backdoor is bigger than max_size, and None if the
+/// puzzle doesn't have a unique solution
+pub fn backdoors<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, max_size: usize) -> Option<Vec<Vec<[usize; 2]>>> {
+    let mut solver = SudokuSolver::new(0);
+    if solver.count_solutions(board, 2) != 1 {
+        return None;
+    }
+    let solution = solver.solve(board).unwrap();
+
+    let filled = SudokuSolver::fill_logically(board, Technique::UniqueCandidate);
+    if filled.is_full() {
+        return Some(vec![Vec::new()]);
+    }
+    let cells: Vec<[usize; 2]> = (0..SIZE * SIZE)
+        .map(|o| [o / SIZE, o % SIZE])
+        .filter(|&[row, col]| filled.get_number(row, col).is_none())
+        .collect();
+
+    for size in 1..=max_size {
+        let mut found = Vec::new();
+        let mut chosen = Vec::with_capacity(size);
+        find_backdoors(&filled, &solution, &cells, size, &mut chosen, &mut found);
+        if !found.is_empty() {
+            return Some(found);
+        }
+    }
+    Some(Vec::new())
+}
+
+/// Try every set of the size made from the cells after the ones chosen
+fn find_backdoors<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, solution: &SudokuBoard<SIZE, BLOCK_SIZE>,
+                                                              cells: &[[usize; 2]], size: usize, chosen: &mut Vec<[usize; 2]>, found: &mut Vec<Vec<[usize; 2]>>) {
+    if chosen.len() == size {
+        let mut board = board.clone();
+        for &[row, col] in chosen.iter() {
+            board.set_cell(solution.get_number(row, col), CellOrigin::Solver, row, col);
+        }
+        if SudokuSolver::fill_logically(&board, Technique::UniqueCandidate).is_full() {
+            found.push(chosen.clone());
+        }
+        return;
+    }
+
+    for (index, &cell) in cells.iter().enumerate() {
+        chosen.push(cell);
+        find_backdoors(board, solution, &cells[index + 1..], size, chosen, found);
+        chosen.pop();
+    }
+}
+
+/// Find the guesses that spare the solver from backtracking: after it fills what it can without
+/// guessing, the numbers that let it finish the puzzle without another guess when tried in the cell
+/// it guesses at. Empty if the solver doesn't need to guess
+pub fn key_guesses<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> Vec<KeyGuess> {
+    // The same techniques and cell as SudokuSolver::solve
+    let filled = SudokuSolver::fill_logically(board, DEFAULT_MAX_TECHNIQUE);
+    if filled.is_full() {
+        return Vec::new();
+    }
+    let Some([row, col]) = SudokuSolver::find_next_to_try(&filled) else { return Vec::new(); };
+
+    let mut result = Vec::new();
+    for num in filled.get_possible(row, col).as_vec() {
+        let mut board = filled.clone();
+        board.set_cell(Some(num), CellOrigin::Solver, row, col);
+        let solved = SudokuSolver::fill_logically(&board, DEFAULT_MAX_TECHNIQUE);
+        if solved.is_full() && solved.find_error().is_none() {
+            result.push(KeyGuess { num, row, col });
+        }
+    }
+    result
+}
+
+/// Find the backdoors of at most max_size cells and the key guesses of the puzzle
+pub fn backdoor_report<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, max_size: usize) -> BackdoorReport {
+    BackdoorReport {
+        backdoors: backdoors(board, max_size),
+        key_guesses: key_guesses(board),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use crate::analysis::{analyze, backdoor_report, backdoors, key_guesses, KeyGuess};
+    use crate::generator::{generate, Difficulty, GeneratorOptions, Symmetry};
+    use crate::solve_report::{Message, Technique};
+    use crate::sudoku_board::{DefaultBoard, SudokuBoard};
+    use crate::sudoku_examples::HARD_LITERALS;
+    use crate::sudoku_solver::SudokuSolver;
+
+    #[test]
+    fn analyze_puzzle() {
+        let info = analyze(&DefaultBoard::from_literal(HARD_LITERALS[0]));
+        assert!(info.unique);
+        assert_eq!(info.difficulty, Some(Difficulty::Medium));
+        assert_eq!(info.hardest_technique, Some(Technique::UniqueCandidate));
+        assert_eq!(info.clues, info.row_clues.iter().sum::<usize>());
+        assert_eq!(info.clues, info.block_clues.iter().sum::<usize>());
+
+        // Numbers entered by the player aren't clues
+        let mut progress = DefaultBoard::from_literal(HARD_LITERALS[0]);
+        let empty = (0..81).map(|o| [o / 9, o % 9]).find(|&[r, c]| progress.get_number(r, c).is_none()).unwrap();
+        assert!(progress.set_user_number(Some(1), empty[0], empty[1]));
+        assert_eq!(analyze(&progress), info);
+
+        let options = GeneratorOptions { symmetry: Symmetry::Rotate90, ..GeneratorOptions::default() };
+        let puzzle = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(1)).unwrap().puzzle;
+        let info = analyze(&puzzle);
+        assert!(info.symmetries.contains(&Symmetry::Rotate180) && info.symmetries.contains(&Symmetry::Rotate90));
+        assert!(info.unique);
+
+        let options = GeneratorOptions { minimal: true, ..GeneratorOptions::default() };
+        let puzzle = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(1)).unwrap().puzzle;
+        assert!(analyze(&puzzle).minimal);
+
+        let board = SudokuBoard::<4, 2>::from_literal("1 _ _ _ _ _ _ _ _ _ _ _ _ _ _ 1");
+        let info = analyze(&board);
+        assert_eq!(info.missing_digits, vec![2, 3, 4]);
+        assert_eq!(info.symmetries, vec![Symmetry::Rotate180, Symmetry::Diagonal]);
+        assert_eq!(info.block_clues, vec![1, 0, 0, 1]);
+        assert!(!info.unique && !info.minimal && info.difficulty.is_none());
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.ends_with(r#""backdoorSize":null}"#));
+        assert!(json.starts_with(r#"{"clues":2,"rowClues":[1,0,0,1],"colClues":[1,0,0,1],"blockClues":[1,0,0,1],"missingDigits":[2,3,4],"#));
+    }
+
+    #[test]
+    fn find_backdoors() {
+        let easy = DefaultBoard::from_literal(HARD_LITERALS[0]);
+        assert_eq!(backdoors(&easy, 2), Some(vec![Vec::new()]));
+        assert!(key_guesses(&easy).is_empty());
+        assert_eq!(analyze(&easy).backdoor_size, Some(0));
+
+        let hard = DefaultBoard::from_literal("
+            8 _ _ _ _ _ _ _ _
+            _ _ 3 6 _ _ _ _ _
+            _ 7 _ _ 9 _ 2 _ _
+            _ 5 _ _ _ 7 _ _ _
+            _ _ _ _ 4 5 7 _ _
+            _ _ _ 1 _ _ _ 3 _
+            _ _ 1 _ _ _ _ 6 8
+            _ _ 8 5 _ _ _ 1 _
+            _ 9 _ _ _ _ 4 _ _");
+        let solution = SudokuSolver::new(0).solve(&hard).unwrap();
+        assert_eq!(backdoors(&hard, 1), Some(Vec::new()));
+        let found = backdoors(&hard, 2).unwrap();
+        assert!(found.contains(&vec![[0, 2], [4, 1]]));
+        assert!(found.iter().all(|o| o.len() == 2));
+        for backdoor in &found {
+            let mut board = hard.clone();
+            for &[row, col] in backdoor {
+                assert!(hard.get_number(row, col).is_none());
+                board.set_number(solution.get_number(row, col), row, col);
+            }
+            assert!(SudokuSolver::fill_logically(&board, Technique::UniqueCandidate).is_full());
+        }
+
+        // Wherever the solver makes its first guess, it has to guess again
+        assert!(key_guesses(&hard).is_empty());
+        assert_eq!(analyze(&hard).backdoor_size, Some(2));
+
+        let mut easier = hard.clone();
+        easier.set_number(solution.get_number(0, 2), 0, 2);
+        assert!(backdoors(&easier, 2).unwrap().contains(&vec![[4, 1]]));
+        assert!(key_guesses(&easier).iter().all(|&KeyGuess { num, row, col }| solution.get_number(row, col) == Some(num)));
+
+        // The 1s and 2s of the empty cells can be swapped, so either guess finishes the board. The
+        // solver guesses in the last cell with the fewest candidates
+        let rectangle = SudokuBoard::<4, 2>::from_literal("_ _ 3 4 3 4 1 2 _ _ 4 3 4 3 2 1");
+        assert!(backdoors(&rectangle, 2).is_none());
+        assert_eq!(key_guesses(&rectangle), vec![KeyGuess { num: 1, row: 2, col: 1 }, KeyGuess { num: 2, row: 2, col: 1 }]);
+        let mut solver = SudokuSolver::new(1);
+        solver.solve(&rectangle);
+        assert!(matches!(solver.steps[0].message, Message::Tried { row: 2, col: 1, .. }));
+
+        let report = backdoor_report(&hard, 2);
+        assert_eq!(report.backdoors, Some(found));
+        assert!(serde_json::to_string(&report).unwrap().starts_with(r#"{"backdoors":[[["#));
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::Path;
+use std::process::exit;
+use wasm::corpus::{write_corpus, CorpusOptions};
+use wasm::generator::{Difficulty, Symmetry};
+
+const USAGE: &str = "Usage: generate_corpus <file.csv> <count> [--size 4|9|16] [--difficulty easy|medium|hard|expert] \
+[--symmetry none|rotate180|rotate90|horizontal|vertical|diagonal] [--seed <number>]";
+
+/// Generate puzzles to a CSV file, resuming from the puzzles already in it.
+/// Run with `cargo run --release --bin generate_corpus -- puzzles.csv 1000 --difficulty hard`
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 || !args.len().is_multiple_of(2) {
+        fail(USAGE);
+    }
+
+    let path = Path::new(&args[0]);
+    let mut options = CorpusOptions {
+        count: args[1].parse().unwrap_or_else(|_| fail("Invalid count")),
+        difficulty: Difficulty::Medium,
+        symmetry: Symmetry::None,
+        seed: 0,
+    };
+    let mut size = 9;
+
+    for pair in args[2..].chunks(2) {
+        let value = &pair[1];
+        match pair[0].as_str() {
+            "--size" => size = value.parse().unwrap_or_else(|_| fail("Invalid size")),
+            "--difficulty" => options.difficulty = parse_name(value).unwrap_or_else(|| fail("Invalid difficulty")),
+            "--symmetry" => options.symmetry = parse_name(value).unwrap_or_else(|| fail("Invalid symmetry")),
+            "--seed" => options.seed = value.parse().unwrap_or_else(|_| fail("Invalid seed")),
+            _ => fail(USAGE),
+        }
+    }
+
+    let result = match size {
+        4 => write_corpus::<4, 2>(path, &options),
+        9 => write_corpus::<9, 3>(path, &options),
+        16 => write_corpus::<16, 4>(path, &options),
+        _ => fail("Invalid size"),
+    };
+    match result {
+        Ok(written) => println!("Wrote {} puzzles to {}", written, path.display()),
+        Err(error) => fail(&error.to_string()),
+    }
+}
+
+/// Read an enum from its name in the JSON schema
+fn parse_name<T: serde::de::DeserializeOwned>(name: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_owned())).ok()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
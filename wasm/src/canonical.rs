@@ -0,0 +1,190 @@
+use crate::sudoku_board::SudokuBoard;
+use crate::util::Array2D;
+
+// Minlex canonical form: of every board that can be made from a puzzle by relabeling the numbers,
+// swapping rows inside bands, columns inside stacks, bands, stacks and transposing, the one whose
+// cells, read row by row with empty cells as 0, are the smallest. Two puzzles are equivalent when
+// they have the same canonical form. Every column order is tried, and rows are chosen one at a
+// time, only following the ones that give the smallest row so far. That's fast for 4x4 and 9x9,
+// but 16x16 boards have millions of column orders
+
+/// Return the canonical form of the numbers of the board, as givens. The rules of variants are
+/// ignored
+pub fn canonical_form<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> SudokuBoard<SIZE, BLOCK_SIZE> {
+    let numbers = board.numbers;
+    let mut transposed = [[None; SIZE]; SIZE];
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            transposed[col][row] = numbers[row][col];
+        }
+    }
+
+    let orders = permutations(BLOCK_SIZE);
+    let mut best: Option<Vec<u8>> = None;
+    for grid in [numbers, transposed] {
+        // One permutation for the stacks and one for the columns inside each stack
+        let mut indices = vec![0; BLOCK_SIZE + 1];
+        loop {
+            let mut permuted = [[0; SIZE]; SIZE];
+            for (index, &stack) in orders[indices[0]].iter().enumerate() {
+                for (offset, &col) in orders[indices[index + 1]].iter().enumerate() {
+                    for row in 0..SIZE {
+                        permuted[row][index * BLOCK_SIZE + offset] = grid[row][stack * BLOCK_SIZE + col].unwrap_or(0);
+                    }
+                }
+            }
+
+            let mut search = Search::<SIZE, BLOCK_SIZE> { grid: &permuted, best: &mut best, current: Vec::with_capacity(SIZE * SIZE) };
+            search.choose_row(&mut Vec::with_capacity(SIZE), [0; SIZE], 1);
+
+            // Next combination, like an odometer
+            let Some(position) = indices.iter().position(|&o| o + 1 < orders.len()) else { break; };
+            indices[position] += 1;
+            indices[..position].fill(0);
+        }
+    }
+
+    let best = best.unwrap();
+    let mut result = SudokuBoard::new();
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            let num = best[row * SIZE + col];
+            if num != 0 {
+                result.set_number(Some(num), row, col);
+            }
+        }
+    }
+    result
+}
+
+/// Whether one puzzle can be made from the other by relabeling the numbers, swapping rows,
+/// columns, bands and stacks, transposing or rotating
+pub fn are_equivalent<const SIZE: usize, const BLOCK_SIZE: usize>(a: &SudokuBoard<SIZE, BLOCK_SIZE>, b: &SudokuBoard<SIZE, BLOCK_SIZE>) -> bool {
+    let count = |board: &SudokuBoard<SIZE, BLOCK_SIZE>| board.numbers.iter().flatten().filter(|o| o.is_some()).count();
+    count(a) == count(b) && canonical_form(a).numbers == canonical_form(b).numbers
+}
+
+/// Every order of the numbers from 0 to n - 1
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for rest in permutations(n - 1) {
+        for index in 0..=rest.len() {
+            let mut order = rest.clone();
+            order.insert(index, n - 1);
+            result.push(order);
+        }
+    }
+    result
+}
+
+struct Search<'a, const SIZE: usize, const BLOCK_SIZE: usize> {
+    /// Board with its columns in the order being tried and 0 for empty cells
+    grid: &'a Array2D<u8, SIZE>,
+    /// Smallest board found so far, row by row
+    best: &'a mut Option<Vec<u8>>,
+    /// Relabeled rows chosen so far
+    current: Vec<u8>,
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> Search<'_, SIZE, BLOCK_SIZE> {
+    /// Try every row that can come next and keep going with the ones that give the smallest
+    /// relabeled row, unless the rows so far are already bigger than the best board. Numbers get
+    /// labels in the order they first appear
+    fn choose_row(&mut self, chosen: &mut Vec<usize>, labels: [u8; SIZE], next_label: u8) {
+        let depth = chosen.len();
+        if depth == SIZE {
+            if self.best.as_ref().is_none_or(|best| self.current < *best) {
+                *self.best = Some(self.current.clone());
+            }
+            return;
+        }
+
+        // Rows of the band being filled, or the first row of any band not used yet
+        let candidates: Vec<usize> = if !depth.is_multiple_of(BLOCK_SIZE) {
+            let band = chosen[depth - depth % BLOCK_SIZE] / BLOCK_SIZE;
+            (band * BLOCK_SIZE..(band + 1) * BLOCK_SIZE).filter(|o| !chosen.contains(o)).collect()
+        } else {
+            (0..SIZE).filter(|&row| chosen.iter().all(|o| o / BLOCK_SIZE != row / BLOCK_SIZE)).collect()
+        };
+
+        let mut options = Vec::with_capacity(candidates.len());
+        for row in candidates {
+            let mut labels = labels;
+            let mut next_label = next_label;
+            let relabeled: Vec<u8> = self.grid[row].iter()
+                .map(|&num| {
+                    if num == 0 { return 0; }
+                    if labels[num as usize - 1] == 0 {
+                        labels[num as usize - 1] = next_label;
+                        next_label += 1;
+                    }
+                    labels[num as usize - 1]
+                })
+                .collect();
+            options.push((relabeled, row, labels, next_label));
+        }
+
+        let smallest = options.iter().map(|o| &o.0).min().unwrap().clone();
+        for (relabeled, row, labels, next_label) in options {
+            if relabeled != smallest { continue; }
+
+            self.current.extend_from_slice(&relabeled);
+            // The best board can change while trying the other rows
+            let end = (depth + 1) * SIZE;
+            if self.best.as_ref().is_none_or(|best| self.current[..] <= best[..end]) {
+                chosen.push(row);
+                self.choose_row(chosen, labels, next_label);
+                chosen.pop();
+            }
+            self.current.truncate(depth * SIZE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use crate::canonical::{are_equivalent, canonical_form};
+    use crate::sudoku_board::{DefaultBoard, SudokuBoard};
+    use crate::sudoku_examples::{EASY_LITERALS, HARD_LITERALS};
+    use crate::sudoku_solver::SudokuSolver;
+    use crate::transform::random_transform;
+
+    #[test]
+    fn canonical_solution() {
+        // Every 4x4 solution is equivalent to one of two
+        let first = SudokuBoard::<4, 2>::from_literal("1 2 3 4 3 4 1 2 2 1 4 3 4 3 2 1");
+        assert_eq!(canonical_form(&first).to_literal(), "1 2 3 4 3 4 1 2 2 1 4 3 4 3 2 1 ");
+        let second = SudokuBoard::<4, 2>::from_literal("1 2 3 4 3 4 1 2 2 3 4 1 4 1 2 3");
+        assert!(!are_equivalent(&first, &second));
+
+        // Any row of a full board can be relabeled to 1 to 9
+        let solution = SudokuSolver::new(0).solve(&DefaultBoard::from_literal(EASY_LITERALS[0])).unwrap();
+        let canonical = canonical_form(&solution);
+        assert!(canonical.to_literal().starts_with("1 2 3 4 5 6 7 8 9 "));
+        assert!(canonical.find_error().is_none());
+    }
+
+    #[test]
+    fn equivalent_puzzles() {
+        let mut rand = ChaCha8Rng::seed_from_u64(8);
+        for literal in [EASY_LITERALS[0], HARD_LITERALS[0]] {
+            let puzzle = DefaultBoard::from_literal(literal);
+            let canonical = canonical_form(&puzzle);
+            for _ in 0..3 {
+                let transformed = random_transform(&puzzle, &mut rand).unwrap();
+                assert_eq!(canonical_form(&transformed).numbers, canonical.numbers);
+                assert!(are_equivalent(&puzzle, &transformed));
+            }
+        }
+
+        let mut other = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        let [row, col] = (0..81).map(|o| [o / 9, o % 9]).find(|&[r, c]| other.get_number(r, c).is_some()).unwrap();
+        other.set_number(None, row, col);
+        assert!(!are_equivalent(&DefaultBoard::from_literal(EASY_LITERALS[0]), &other));
+    }
+}
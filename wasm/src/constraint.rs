@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use crate::number_options::NumberOptions;
+use crate::solve_report::Message;
+
+/// Rule of a variant puzzle about a group of cells. The board reads the numbers of the cells to
+/// find errors, and the solver uses the candidates of the cells to remove the ones that can't
+/// follow the rule. Besides the built-in lines, pairs and outside clues, other crates can implement
+/// it and add new rules to a board with [crate::rules::Rules::add_constraint]. Rules must be Send
+/// and Sync, so boards can be moved across threads
+pub trait Constraint<const SIZE: usize>: Debug + Send + Sync {
+    /// Cells the rule is about, as [row, col]
+    fn cells(&self) -> Cow<'_, [[usize; 2]]>;
+
+    /// Whether the numbers of the cells, in the same order as [Constraint::cells], break the rule.
+    /// Empty cells are None, and only break the rule when no number could fill them
+    fn is_broken(&self, numbers: &[Option<u8>]) -> bool;
+
+    /// Return the numbers each cell can contain according to the rule, given the options of
+    /// every cell in the same order as [Constraint::cells]. Filled cells have a single option
+    fn restrict(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>>;
+
+    /// Explain the candidates removed by [Constraint::restrict]
+    fn message(&self) -> Message;
+
+    /// Row and column to highlight in the report instead of the cells, for rules about a whole
+    /// row or column
+    fn highlight_line(&self) -> [Option<usize>; 2] {
+        [None, None]
+    }
+}
@@ -0,0 +1,130 @@
+use std::fs::OpenOptions;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use crate::formats::{import_as, to_line, PuzzleFormat};
+use crate::generator::{generate_graded, grade, Difficulty, Symmetry};
+use crate::sudoku_solver::SudokuSolver;
+
+/// Attempts to generate each puzzle before giving up on the whole corpus
+const ATTEMPTS: usize = 1000;
+
+/// Options of [write_corpus]
+#[derive(Clone, Debug)]
+pub struct CorpusOptions {
+    pub count: usize,
+    pub difficulty: Difficulty,
+    pub symmetry: Symmetry,
+    pub seed: u64,
+}
+
+/// Write generated puzzles to a CSV file until it has the number of puzzles asked for, one per line
+/// as "puzzle,solution,grade,technique", like test_data/tests_4000.csv with two more columns. The
+/// technique is the hardest one needed, or "guess". Each puzzle is read back from its line and
+/// must have a unique solution before it's written. Puzzles already in the file are kept, so an
+/// interrupted run can be resumed, and each puzzle only depends on the options and its line, so the
+/// result is the same. The first line is generated again to check that the file was written with
+/// the same options. Return the number of puzzles written
+pub fn write_corpus<const SIZE: usize, const BLOCK_SIZE: usize>(path: &Path, options: &CorpusOptions) -> io::Result<usize> {
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+
+    // A line without its end was being written when the run stopped
+    let complete = content.rfind('\n').map_or(0, |o| o + 1);
+    let existing = content[..complete].lines().count();
+
+    let mut solver = SudokuSolver::new(0);
+    if let Some(first) = content[..complete].lines().next() {
+        if corpus_line::<SIZE, BLOCK_SIZE>(0, options, &mut solver)? != first {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "The file was written with other options"));
+        }
+    }
+
+    file.set_len(complete as u64)?;
+    file.seek(SeekFrom::End(0))?;
+
+    for index in existing..options.count {
+        writeln!(file, "{}", corpus_line::<SIZE, BLOCK_SIZE>(index, options, &mut solver)?)?;
+        file.flush()?;
+    }
+
+    Ok(options.count.saturating_sub(existing))
+}
+
+/// Generate the puzzle of the line with the index and write it as a line of the corpus, without the
+/// line end
+fn corpus_line<const SIZE: usize, const BLOCK_SIZE: usize>(index: usize, options: &CorpusOptions,
+                                                           solver: &mut SudokuSolver<SIZE, BLOCK_SIZE>) -> io::Result<String> {
+    let mut rand = ChaCha8Rng::seed_from_u64(options.seed);
+    rand.set_stream(index as u64);
+
+    let generated = generate_graded::<SIZE, BLOCK_SIZE, _>(options.difficulty, options.symmetry, ATTEMPTS, &mut rand)
+        .ok_or_else(|| io::Error::other(format!("Couldn't generate puzzle {}", index + 1)))?;
+    // Empty cells are written as '_', like in test_data/tests_4000.csv
+    let line = to_line(&generated.puzzle).replace('.', "_");
+
+    // Everything is checked on the puzzle read back from the line, as the users of the file will
+    let puzzle = import_as::<SIZE, BLOCK_SIZE>(&line, PuzzleFormat::Line)
+        .map_err(|_| io::Error::other(format!("Puzzle {} can't be read back", index + 1)))?
+        .board;
+    if solver.count_solutions(&puzzle, 2) != 1 {
+        return Err(io::Error::other(format!("Puzzle {} doesn't have a unique solution", index + 1)));
+    }
+    let solution = solver.solve(&puzzle).expect("Unique puzzles have a solution");
+    let (difficulty, technique) = grade(&puzzle);
+
+    Ok(format!("{},{},{},{}", line, to_line(&solution), difficulty.name(), technique.map_or("guess", |o| o.name())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::corpus::{write_corpus, CorpusOptions};
+    use crate::formats::{import_as, PuzzleFormat};
+    use crate::generator::{Difficulty, Symmetry};
+    use crate::sudoku_solver::SudokuSolver;
+
+    #[test]
+    fn resumable_corpus() {
+        // Named after the process, so parallel runs don't share the files
+        let dir = std::env::temp_dir();
+        let full = dir.join(format!("sudoku_corpus_full_{}.csv", std::process::id()));
+        let resumed = dir.join(format!("sudoku_corpus_resumed_{}.csv", std::process::id()));
+        let _ = fs::remove_file(&full);
+        let _ = fs::remove_file(&resumed);
+
+        let options = CorpusOptions { count: 3, difficulty: Difficulty::Medium, symmetry: Symmetry::Rotate180, seed: 4 };
+        assert_eq!(write_corpus::<9, 3>(&full, &options).unwrap(), 3);
+        let content = fs::read_to_string(&full).unwrap();
+
+        let mut solver = SudokuSolver::new(0);
+        for line in content.lines() {
+            let columns: Vec<&str> = line.split(',').collect();
+            assert_eq!(&columns[2..], ["medium", "uniqueCandidate"]);
+            assert!(columns[0].contains('_') && !columns[0].contains('.'));
+            let puzzle = import_as::<9, 3>(columns[0], PuzzleFormat::Line).ok().unwrap().board;
+            let solution = import_as::<9, 3>(columns[1], PuzzleFormat::Line).ok().unwrap().board;
+            assert_eq!(solver.count_solutions(&puzzle, 2), 1);
+            assert!(solver.solve(&puzzle).unwrap().same_numbers(&solution));
+        }
+
+        // Stopped in the middle of the second line
+        let first_end = content.find('\n').unwrap() + 1;
+        fs::write(&resumed, &content[..first_end + 10]).unwrap();
+        assert_eq!(write_corpus::<9, 3>(&resumed, &options).unwrap(), 2);
+        assert_eq!(fs::read_to_string(&resumed).unwrap(), content);
+        assert_eq!(write_corpus::<9, 3>(&resumed, &options).unwrap(), 0);
+
+        // Resuming with other options would mix puzzles of both
+        let other = CorpusOptions { seed: 5, count: 4, ..options.clone() };
+        assert_eq!(write_corpus::<9, 3>(&resumed, &other).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        let other = CorpusOptions { difficulty: Difficulty::Easy, count: 4, ..options };
+        assert!(write_corpus::<9, 3>(&resumed, &other).is_err());
+        assert_eq!(fs::read_to_string(&resumed).unwrap(), content);
+
+        fs::remove_file(full).unwrap();
+        fs::remove_file(resumed).unwrap();
+    }
+}
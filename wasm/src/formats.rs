@@ -0,0 +1,579 @@
+use serde::{Deserialize, Serialize};
+use crate::number_options::NumberOptions;
+use crate::sudoku_board::{CellOrigin, SudokuBoard};
+use crate::sudoku_solver::Possibilities;
+
+/// Puzzle formats used by other sudoku tools.
+/// Numbers above 9 are written as letters, so 10 is 'A' and 16 is 'G'. Empty cells are written as
+/// '.', and '0' or '_' are also accepted when reading.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PuzzleFormat {
+    /// The space-separated format of [SudokuBoard::from_literal], like "1 2 _ 4"
+    Literal,
+    /// All the cells in a single line, like "12.4..."
+    Line,
+    /// SudokuPuzzle (.sdk) file: one line per row, and lines starting with '#' are comments
+    Sdk,
+    /// Simple Sudoku (.ss) file: one line per row, with '|' and '-' separating the blocks
+    SimpleSudoku,
+    /// SadMan Sudoku file: a [Puzzle] section with the givens, an optional [State] section with the
+    /// numbers filled by the player and an optional [PencilMarks] section with the candidates of
+    /// each cell, separated by ','
+    SadMan,
+    /// Grid with the candidates of every cell, like "1 25 379". Cells with a single number are
+    /// read as filled, and the candidates of the other cells restrict what the solver can place.
+    /// Blocks may be separated by '|' and by lines made of '-', '+', '.', ':' or '\''
+    PencilMarks,
+}
+
+/// Serialized as {"type", "value"}, where "type" is "cellCount", "character" or "number"
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum FormatError {
+    /// The text doesn't contain the right number of cells
+    #[serde(rename = "cellCount")]
+    WrongCellCount(usize),
+    /// A character that doesn't represent a number or an empty cell, by line and column
+    #[serde(rename = "character")]
+    InvalidCharacter(usize, usize),
+    /// A value that doesn't fit in the board, by row and column
+    #[serde(rename = "number")]
+    InvalidNumber(usize, usize),
+}
+
+/// Board read from a file, with the candidates of each cell if the format stores them
+pub struct ImportedPuzzle<const SIZE: usize, const BLOCK_SIZE: usize> {
+    pub format: PuzzleFormat,
+    pub board: SudokuBoard<SIZE, BLOCK_SIZE>,
+    pub pencil_marks: Option<Possibilities<SIZE>>,
+}
+
+impl PuzzleFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        use PuzzleFormat::*;
+        match name {
+            "literal" => Some(Literal),
+            "line" => Some(Line),
+            "sdk" => Some(Sdk),
+            "ss" => Some(SimpleSudoku),
+            "sadman" => Some(SadMan),
+            "pencil" => Some(PencilMarks),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        use PuzzleFormat::*;
+        match self {
+            Literal => "literal",
+            Line => "line",
+            Sdk => "sdk",
+            SimpleSudoku => "ss",
+            SadMan => "sadman",
+            PencilMarks => "pencil",
+        }
+    }
+
+    /// Guess the format of a text for a board of the size by its shape
+    pub fn detect<const SIZE: usize>(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines()
+            .map(str::trim)
+            .filter(|o| !o.is_empty() && !o.starts_with('#'))
+            .collect();
+        let tokens: Vec<&str> = lines.iter().flat_map(|o| o.split_whitespace()).collect();
+
+        // Literals have a number, which may be above 9, or '_' for each cell, and may mark the
+        // origin of numbers, like "5+". Those marks only appear in literals, so the text is read
+        // as one even with the wrong number of cells, which is then reported by the import. Pencil
+        // marks of a full board have a digit for each cell too, but 16x16 boards write the numbers
+        // above 9 as letters
+        let is_token = |token: &str| {
+            let token = token.trim_end_matches(['+', '*']);
+            token == "_" || !token.is_empty() && token.chars().all(|c| c.is_ascii_digit())
+        };
+        let is_cell = |token: &str| {
+            let token = token.trim_end_matches(['+', '*']);
+            token == "_" || token.parse::<usize>().is_ok_and(|o| (1..=SIZE).contains(&o))
+        };
+        let is_marked = |token: &str| token == "_" || token.ends_with(['+', '*']);
+        let is_literal = tokens.iter().all(|o| is_token(o))
+            && (tokens.iter().any(|o| is_marked(o)) || tokens.len() == SIZE * SIZE && tokens.iter().all(|o| is_cell(o)));
+
+        if lines.first().is_some_and(|o| o.starts_with('[')) {
+            PuzzleFormat::SadMan
+        } else if is_literal {
+            PuzzleFormat::Literal
+        } else if lines.iter().any(|o| o.contains(' ')) {
+            PuzzleFormat::PencilMarks
+        } else if lines.iter().any(|o| o.contains('|')) {
+            PuzzleFormat::SimpleSudoku
+        } else if lines.len() == 1 {
+            PuzzleFormat::Line
+        } else {
+            PuzzleFormat::Sdk
+        }
+    }
+}
+
+/// Convert a character to a number. Return Some(None) for empty cells and None for characters
+/// that don't represent a cell
+pub(crate) fn char_to_cell(c: char) -> Option<Option<u8>> {
+    match c {
+        '.' | '0' | '_' => Some(None),
+        '1'..='9' => Some(Some(c as u8 - b'0')),
+        'A'..='Z' => Some(Some(c as u8 - b'A' + 10)),
+        'a'..='z' => Some(Some(c as u8 - b'a' + 10)),
+        _ => None,
+    }
+}
+
+fn number_to_char(num: u8) -> char {
+    if num <= 9 {
+        (b'0' + num) as char
+    } else {
+        (b'A' + num - 10) as char
+    }
+}
+
+pub(crate) fn cell_to_char(value: Option<u8>) -> char {
+    value.map_or('.', number_to_char)
+}
+
+/// Read a board from text in any supported format, detecting which one is used
+pub fn import<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str) -> Result<ImportedPuzzle<SIZE, BLOCK_SIZE>, FormatError> {
+    import_as(text, PuzzleFormat::detect::<SIZE>(text))
+}
+
+pub fn import_as<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str, format: PuzzleFormat)
+                                                             -> Result<ImportedPuzzle<SIZE, BLOCK_SIZE>, FormatError> {
+    use PuzzleFormat::*;
+    let (board, pencil_marks) = match format {
+        Literal => (read_literal(text)?, None),
+        Line | Sdk => (read_cells(text.lines().filter(|o| !o.trim_start().starts_with('#')),
+                                  CellOrigin::Given)?, None),
+        SimpleSudoku => (read_cells(text.lines(), CellOrigin::Given)?, None),
+        SadMan => read_sadman(text)?,
+        PencilMarks => read_pencil_marks(text)?,
+    };
+
+    Ok(ImportedPuzzle { format, board, pencil_marks })
+}
+
+/// Read a board from lines of characters, ignoring anything that isn't a cell, like block borders
+fn read_cells<'a, const SIZE: usize, const BLOCK_SIZE: usize>(lines: impl Iterator<Item=&'a str>, origin: CellOrigin)
+                                                              -> Result<SudokuBoard<SIZE, BLOCK_SIZE>, FormatError> {
+    let mut board = SudokuBoard::new();
+    let mut index = 0;
+
+    for (line_index, line) in lines.enumerate() {
+        for (char_index, c) in line.chars().enumerate() {
+            if c.is_whitespace() || matches!(c, '|' | '-' | '+' | '*') { continue; }
+
+            let cell = char_to_cell(c).ok_or(FormatError::InvalidCharacter(line_index, char_index))?;
+            if index >= SIZE * SIZE {
+                return Err(FormatError::WrongCellCount(index + 1));
+            }
+
+            let row = index / SIZE;
+            let col = index % SIZE;
+            if let Some(num) = cell {
+                if num as usize > SIZE {
+                    return Err(FormatError::InvalidNumber(row, col));
+                }
+                board.set_cell(Some(num), origin, row, col);
+            }
+            index += 1;
+        }
+    }
+
+    if index != SIZE * SIZE {
+        return Err(FormatError::WrongCellCount(index));
+    }
+    Ok(board)
+}
+
+/// Split a line in the tokens between whitespace or the separator, with the index of the first
+/// character of each
+fn tokens_with_positions(line: &str, separator: impl Fn(char) -> bool) -> Vec<(usize, &str)> {
+    let mut result = Vec::new();
+    // Character and byte index where the current token starts
+    let mut start = None;
+
+    for (char_index, (byte_index, c)) in line.char_indices().enumerate() {
+        let is_separator = c.is_whitespace() || separator(c);
+        match start {
+            None if !is_separator => start = Some((char_index, byte_index)),
+            Some((first, first_byte)) if is_separator => {
+                result.push((first, &line[first_byte..byte_index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((first, first_byte)) = start {
+        result.push((first, &line[first_byte..]));
+    }
+    result
+}
+
+/// Read a board in the format of [SudokuBoard::from_literal], checking that there's a number in
+/// the board or '_' for each cell
+fn read_literal<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str) -> Result<SudokuBoard<SIZE, BLOCK_SIZE>, FormatError> {
+    let mut tokens = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        for (char_index, token) in tokens_with_positions(line, |_| false) {
+            let index = tokens.len();
+            let value = token.trim_end_matches(['+', '*']);
+            if value != "_" {
+                if let Some(position) = value.chars().position(|c| !c.is_ascii_digit()) {
+                    return Err(FormatError::InvalidCharacter(line_index, char_index + position));
+                }
+                if value.is_empty() {
+                    return Err(FormatError::InvalidCharacter(line_index, char_index));
+                }
+                if !value.parse::<usize>().is_ok_and(|o| (1..=SIZE).contains(&o)) {
+                    return Err(FormatError::InvalidNumber(index / SIZE, index % SIZE));
+                }
+            }
+            tokens.push(token);
+        }
+    }
+
+    if tokens.len() != SIZE * SIZE {
+        return Err(FormatError::WrongCellCount(tokens.len()));
+    }
+    Ok(SudokuBoard::from_literal(&tokens.join(" ")))
+}
+
+/// Return the lines of a section like [Puzzle] in a SadMan file
+fn sadman_section<'a>(text: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let mut lines = text.lines().map(str::trim).skip_while(|o| *o != name);
+    lines.next()?;
+
+    Some(lines
+        .take_while(|o| !o.starts_with('['))
+        .filter(|o| !o.is_empty())
+        .collect())
+}
+
+#[allow(clippy::type_complexity)]
+fn read_sadman<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str)
+                                                           -> Result<(SudokuBoard<SIZE, BLOCK_SIZE>, Option<Possibilities<SIZE>>), FormatError> {
+    let puzzle = sadman_section(text, "[Puzzle]").ok_or(FormatError::WrongCellCount(0))?;
+    let mut board: SudokuBoard<SIZE, BLOCK_SIZE> = read_cells(puzzle.into_iter(), CellOrigin::Given)?;
+
+    // The state contains the givens and the numbers filled by the player
+    if let Some(state) = sadman_section(text, "[State]") {
+        let state: SudokuBoard<SIZE, BLOCK_SIZE> = read_cells(state.into_iter(), CellOrigin::User)?;
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if board.get_number(row, col).is_none() {
+                    board.set_cell(state.get_number(row, col), CellOrigin::User, row, col);
+                }
+            }
+        }
+    }
+
+    let pencil_marks = match sadman_section(text, "[PencilMarks]") {
+        None => None,
+        Some(lines) => {
+            if lines.len() != SIZE {
+                return Err(FormatError::WrongCellCount(lines.len() * SIZE));
+            }
+
+            let mut result = [[NumberOptions::default(); SIZE]; SIZE];
+            for (row, line) in lines.into_iter().enumerate() {
+                let cells: Vec<&str> = line.split(',').collect();
+                if cells.len() != SIZE {
+                    return Err(FormatError::WrongCellCount(row * SIZE + cells.len()));
+                }
+
+                for (col, cell) in cells.into_iter().enumerate() {
+                    for (char_index, c) in cell.trim().chars().enumerate() {
+                        match char_to_cell(c) {
+                            Some(Some(num)) if num as usize <= SIZE => result[row][col].add_number(num),
+                            Some(Some(_)) => return Err(FormatError::InvalidNumber(row, col)),
+                            _ => return Err(FormatError::InvalidCharacter(row, char_index)),
+                        }
+                    }
+                }
+            }
+            Some(result)
+        }
+    };
+
+    Ok((board, pencil_marks))
+}
+
+#[allow(clippy::type_complexity)]
+fn read_pencil_marks<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str)
+                                                                 -> Result<(SudokuBoard<SIZE, BLOCK_SIZE>, Option<Possibilities<SIZE>>), FormatError> {
+    let mut board = SudokuBoard::new();
+    let mut pencil_marks = [[NumberOptions::default(); SIZE]; SIZE];
+    let mut index = 0;
+
+    for (line_index, line) in text.lines().enumerate() {
+        // Skip the lines between bands
+        if line.chars().all(|c| c.is_whitespace() || matches!(c, '-' | '+' | '.' | ':' | '\'' | '|' | '*')) {
+            continue;
+        }
+
+        for (char_index, token) in tokens_with_positions(line, |c| c == '|') {
+            if index >= SIZE * SIZE {
+                return Err(FormatError::WrongCellCount(index + 1));
+            }
+            let row = index / SIZE;
+            let col = index % SIZE;
+
+            let mut options = NumberOptions::default();
+            for (position, c) in token.chars().enumerate() {
+                match char_to_cell(c) {
+                    Some(Some(num)) if num as usize <= SIZE => options.add_number(num),
+                    Some(Some(_)) => return Err(FormatError::InvalidNumber(row, col)),
+                    _ => return Err(FormatError::InvalidCharacter(line_index, char_index + position)),
+                }
+            }
+
+            pencil_marks[row][col] = options;
+            if options.count() == 1 {
+                board.set_number(options.first(), row, col);
+            }
+            index += 1;
+        }
+    }
+
+    if index != SIZE * SIZE {
+        return Err(FormatError::WrongCellCount(index));
+    }
+
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            board.restrict(row, col, pencil_marks[row][col]);
+        }
+    }
+    Ok((board, Some(pencil_marks)))
+}
+
+/// Write a board in the chosen format. Pencil marks are only written by formats that support them
+pub fn export<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, format: PuzzleFormat,
+                                                          pencil_marks: Option<&Possibilities<SIZE>>) -> String {
+    use PuzzleFormat::*;
+    match format {
+        Literal => board.to_literal(),
+        Line => to_line(board),
+        Sdk => to_sdk(board),
+        SimpleSudoku => to_simple_sudoku(board),
+        SadMan => to_sadman(board, pencil_marks),
+        PencilMarks => to_pencil_marks(board),
+    }
+}
+
+pub fn to_line<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> String {
+    board.numbers.iter()
+        .flat_map(|row| row.iter())
+        .map(|&o| cell_to_char(o))
+        .collect()
+}
+
+/// Write each row in a line, using the given function to decide which numbers are included
+fn grid_lines<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>,
+                                                          include: impl Fn(usize, usize) -> bool) -> Vec<String> {
+    (0..SIZE)
+        .map(|row| (0..SIZE)
+            .map(|col| cell_to_char(board.get_number(row, col).filter(|_| include(row, col))))
+            .collect())
+        .collect()
+}
+
+pub fn to_sdk<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> String {
+    grid_lines(board, |_, _| true).join("\n") + "\n"
+}
+
+pub fn to_simple_sudoku<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> String {
+    let border = "-".repeat(SIZE + BLOCK_SIZE - 1);
+    let separator = vec!["-".repeat(BLOCK_SIZE); BLOCK_SIZE].join("+");
+    let mut result = format!("*{}*\n", border);
+
+    for (row, line) in grid_lines(board, |_, _| true).into_iter().enumerate() {
+        if row != 0 && row % BLOCK_SIZE == 0 {
+            result += &format!("|{}|\n", separator);
+        }
+
+        let blocks: Vec<String> = line.chars()
+            .collect::<Vec<char>>()
+            .chunks(BLOCK_SIZE)
+            .map(|o| o.iter().collect())
+            .collect();
+        result += &format!("|{}|\n", blocks.join("|"));
+    }
+
+    result + &format!("*{}*\n", border)
+}
+
+pub fn to_sadman<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>,
+                                                             pencil_marks: Option<&Possibilities<SIZE>>) -> String {
+    let mut result = String::from("[Puzzle]\n");
+    for line in grid_lines(board, |row, col| board.is_given(row, col)) {
+        result += &line;
+        result += "\n";
+    }
+
+    result += "\n[State]\n";
+    for line in grid_lines(board, |_, _| true) {
+        result += &line;
+        result += "\n";
+    }
+
+    if let Some(pencil_marks) = pencil_marks {
+        result += "\n[PencilMarks]\n";
+        for row in 0..SIZE {
+            let cells: Vec<String> = (0..SIZE)
+                .map(|col| if board.get_number(row, col).is_some() {
+                    String::new()
+                } else {
+                    pencil_marks[row][col].as_vec().into_iter().map(number_to_char).collect()
+                })
+                .collect();
+            result += &cells.join(",");
+            result += "\n";
+        }
+    }
+
+    result
+}
+
+/// Write the candidates of every cell, aligned in columns, with lines separating the blocks
+pub fn to_pencil_marks<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> String {
+    let tokens: Vec<Vec<String>> = (0..SIZE)
+        .map(|row| (0..SIZE)
+            .map(|col| match board.get_number(row, col) {
+                Some(num) => number_to_char(num).to_string(),
+                None => board.get_possible(row, col).as_vec().into_iter().map(number_to_char).collect(),
+            })
+            .collect())
+        .collect();
+    let width = tokens.iter().flatten().map(String::len).max().unwrap_or(1).max(1);
+
+    let block_width = BLOCK_SIZE * (width + 1) + 1;
+    let separator = vec!["-".repeat(block_width); BLOCK_SIZE].join("+");
+    let mut result = String::new();
+
+    for (row, line) in tokens.into_iter().enumerate() {
+        if row != 0 && row % BLOCK_SIZE == 0 {
+            result += &separator;
+            result += "\n";
+        }
+
+        let blocks: Vec<String> = line.chunks(BLOCK_SIZE)
+            .map(|o| o.iter().map(|token| format!(" {:<width$}", token, width = width)).collect::<String>() + " ")
+            .collect();
+        result += blocks.join("|").trim_end();
+        result += "\n";
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formats::{export, import, import_as, FormatError, PuzzleFormat};
+    use crate::number_options::NumberOptions;
+    use crate::sudoku_board::{CellOrigin, DefaultBoard, SudokuBoard};
+    use crate::sudoku_examples::EASY_LITERALS;
+    use crate::sudoku_solver::SudokuSolver;
+
+    #[test]
+    fn detect_formats() {
+        assert_eq!(PuzzleFormat::detect::<9>(EASY_LITERALS[0]), PuzzleFormat::Literal);
+        assert_eq!(PuzzleFormat::detect::<9>(&".".repeat(81)), PuzzleFormat::Line);
+        assert_eq!(PuzzleFormat::detect::<9>(&"0".repeat(81)), PuzzleFormat::Line);
+        assert_eq!(PuzzleFormat::detect::<9>(&"# Comment\n.........\n".repeat(9)), PuzzleFormat::Sdk);
+        assert_eq!(PuzzleFormat::detect::<9>("*---*\n|..|\n"), PuzzleFormat::SimpleSudoku);
+        assert_eq!(PuzzleFormat::detect::<9>("[Puzzle]\n"), PuzzleFormat::SadMan);
+
+        // Full boards in rows, including numbers above 9
+        let solution = SudokuSolver::new(0).solve(&DefaultBoard::from_literal(EASY_LITERALS[0])).unwrap();
+        let rows = solution.to_literal().split(' ').collect::<Vec<_>>().chunks(9).map(|o| o.join(" ")).collect::<Vec<_>>().join("\n");
+        assert_eq!(PuzzleFormat::detect::<9>(&rows), PuzzleFormat::Literal);
+        let solution = SudokuSolver::new(0).solve(&SudokuBoard::<16, 4>::new()).unwrap();
+        let literal = solution.to_literal();
+        assert_eq!(PuzzleFormat::detect::<16>(&literal), PuzzleFormat::Literal);
+        assert!(import::<16, 4>(&literal).ok().unwrap().board.same_numbers(&solution));
+    }
+
+    #[test]
+    fn invalid_literals() {
+        let literal = DefaultBoard::from_literal(EASY_LITERALS[0]).to_literal();
+        assert_eq!(import::<9, 3>(&(literal.clone() + "_ _")).err(), Some(FormatError::WrongCellCount(83)));
+        assert_eq!(import::<9, 3>("_ 1 2").err(), Some(FormatError::WrongCellCount(3)));
+        assert_eq!(import::<9, 3>(&literal.replacen('_', "12", 1)).err(), Some(FormatError::InvalidNumber(0, 0)));
+        assert_eq!(import::<9, 3>(&literal.replacen('_', "0", 1)).err(), Some(FormatError::InvalidNumber(0, 0)));
+        assert_eq!(import_as::<9, 3>(&literal.replacen('_', "5x", 1), PuzzleFormat::Literal).err(),
+                   Some(FormatError::InvalidCharacter(0, 1)));
+        assert_eq!(import_as::<9, 3>(&format!("_\n  {}", literal.replacen('_', "+", 1)), PuzzleFormat::Literal).err(),
+                   Some(FormatError::InvalidCharacter(1, 2)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let board = DefaultBoard::from_literal(EASY_LITERALS[0]);
+
+        for format in [PuzzleFormat::Literal, PuzzleFormat::Line, PuzzleFormat::Sdk,
+            PuzzleFormat::SimpleSudoku, PuzzleFormat::SadMan] {
+            let text = export(&board, format, None);
+            let imported = import::<9, 3>(&text).ok().unwrap();
+            assert_eq!(imported.format, format);
+            assert!(imported.board.same_numbers(&board));
+        }
+    }
+
+    #[test]
+    fn sadman_state_and_pencil_marks() {
+        let mut board = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        board.set_user_number(Some(8), 0, 0);
+        let mut pencil_marks = [[NumberOptions::<9>::default(); 9]; 9];
+        pencil_marks[0][1].add_number(6);
+        pencil_marks[0][1].add_number(7);
+
+        let text = export(&board, PuzzleFormat::SadMan, Some(&pencil_marks));
+        let imported = import::<9, 3>(&text).ok().unwrap();
+
+        assert_eq!(imported.board.get_origin(0, 0), Some(CellOrigin::User));
+        assert_eq!(imported.board.get_origin(0, 4), Some(CellOrigin::Given));
+        assert_eq!(imported.pencil_marks.unwrap()[0][1].as_vec(), vec![6, 7]);
+    }
+
+    #[test]
+    fn pencil_marks_restrict_candidates() {
+        let mut board = DefaultBoard::new();
+        board.set_number(Some(1), 0, 0);
+        let mut options = NumberOptions::default();
+        options.add_number(4);
+        options.add_number(5);
+        board.restrict(0, 2, options);
+
+        let text = export(&board, PuzzleFormat::PencilMarks, None);
+        assert_eq!(PuzzleFormat::detect::<9>(&text), PuzzleFormat::PencilMarks);
+        let imported = import::<9, 3>(&text).ok().unwrap();
+
+        assert_eq!(imported.board.get_possible(0, 2).as_vec(), vec![4, 5]);
+        assert_eq!(export(&imported.board, PuzzleFormat::PencilMarks, None), text);
+    }
+
+    #[test]
+    fn pencil_marks_all_sizes() {
+        let text = export(&SudokuBoard::<16, 4>::new(), PuzzleFormat::PencilMarks, None);
+        assert!(text.starts_with(" 123456789ABCDEFG 123456789ABCDEFG"));
+        let imported = import::<16, 4>(&text).ok().unwrap();
+        assert!(imported.board.get_possible(15, 15).all());
+
+        let imported = import::<4, 2>("1 2 | 34 34\n34 34 | 1 2\n----+----\n2 1 | 34 34\n34 34 | 2 1").ok().unwrap();
+        assert_eq!(imported.board.get_number(0, 1), Some(2));
+        assert_eq!(imported.board.get_possible(0, 2).as_vec(), vec![3, 4]);
+
+        // The position of the character in the line, not the column of the cell
+        let result = import_as::<4, 2>("1 2 | 34 3?\n34 34 | 1 2\n2 1 | 34 34\n34 34 | 2 1", PuzzleFormat::PencilMarks);
+        assert_eq!(result.err(), Some(FormatError::InvalidCharacter(0, 10)));
+    }
+}
@@ -0,0 +1,575 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::solve_report::{ReportStep, Technique};
+use crate::sudoku_board::SudokuBoard;
+use crate::sudoku_solver::SudokuSolver;
+use crate::util::Array2D;
+
+/// Symmetry of the givens of a generated puzzle
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// The givens look the same after turning the board upside down
+    Rotate180,
+    /// The givens look the same after turning the board a quarter turn
+    Rotate90,
+    /// Mirrored between the top and the bottom halves
+    Horizontal,
+    /// Mirrored between the left and the right halves
+    Vertical,
+    /// Mirrored across the main diagonal
+    Diagonal,
+}
+
+impl Symmetry {
+    /// Return the cells that must be given or empty together with the cell, including itself
+    pub fn orbit<const SIZE: usize>(&self, row: usize, col: usize) -> Vec<[usize; 2]> {
+        let last = SIZE - 1;
+        let mut result = match self {
+            Symmetry::None => vec![[row, col]],
+            Symmetry::Rotate180 => vec![[row, col], [last - row, last - col]],
+            Symmetry::Rotate90 => vec![[row, col], [col, last - row], [last - row, last - col], [last - col, row]],
+            Symmetry::Horizontal => vec![[row, col], [last - row, col]],
+            Symmetry::Vertical => vec![[row, col], [row, last - col]],
+            Symmetry::Diagonal => vec![[row, col], [col, row]],
+        };
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// Split the board in orbits, so that every cell is in exactly one
+    pub fn orbits<const SIZE: usize>(&self) -> Vec<Vec<[usize; 2]>> {
+        let mut result = Vec::new();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let orbit = self.orbit::<SIZE>(row, col);
+                // Each orbit is added once, when reaching its first cell
+                if orbit[0] == [row, col] {
+                    result.push(orbit);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Difficulty band of a puzzle, from the hardest technique needed to solve it.
+/// Serialized in camelCase, like "medium"
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Difficulty {
+    /// Only sole candidates
+    Easy,
+    /// Unique candidates
+    Medium,
+    /// Harder techniques, like X-Wing
+    Hard,
+    /// Needs guessing
+    Expert,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Hardest technique of the band, or None if it needs guessing
+    pub fn max_technique(&self) -> Option<Technique> {
+        match self {
+            Difficulty::Easy => Some(Technique::SoleCandidate),
+            Difficulty::Medium => Some(Technique::UniqueCandidate),
+            Difficulty::Hard => Some(Technique::XWing),
+            Difficulty::Expert => None,
+        }
+    }
+}
+
+/// Return the difficulty of a puzzle and the hardest technique needed to solve it without
+/// guessing, or None if it needs guessing
+pub fn grade<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> (Difficulty, Option<Technique>) {
+    let Some(result) = SudokuSolver::solve_logically(board, Technique::XWing) else {
+        return (Difficulty::Expert, None);
+    };
+
+    let hardest = result.steps.iter().filter_map(|o| o.message.technique()).max();
+    let difficulty = match hardest {
+        None | Some(Technique::SoleCandidate) => Difficulty::Easy,
+        Some(Technique::UniqueCandidate) => Difficulty::Medium,
+        Some(Technique::VariantRule | Technique::XWing) => Difficulty::Hard,
+    };
+    (difficulty, hardest)
+}
+
+/// Options of [generate]. Serialized as {"coverage", "symmetry", "requiredTechnique",
+/// "maxTechnique", "attempts", "minimal"}, where every field is optional, like
+/// {"coverage": 0.3, "symmetry": "rotate180", "requiredTechnique": "xWing"}
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GeneratorOptions {
+    /// Fraction of the cells to keep as givens. Fewer cells can't always be removed without
+    /// allowing more than one solution, so the puzzle may end up with more
+    pub coverage: f64,
+    pub symmetry: Symmetry,
+    /// Technique the puzzle must need at least once. Givens stop being removed as soon as it's
+    /// needed
+    pub required_technique: Option<Technique>,
+    /// Hardest technique the puzzle can need. The puzzle must be solvable without guessing
+    pub max_technique: Option<Technique>,
+    /// Number of solutions to try before giving up on the required technique
+    pub attempts: usize,
+    /// Remove givens until none can be removed, ignoring the coverage. Without techniques, the
+    /// puzzle is then minimal, see [is_minimal]. A last pass over single cells makes it minimal
+    /// even if that breaks the symmetry
+    pub minimal: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            coverage: 0.4,
+            symmetry: Symmetry::None,
+            required_technique: None,
+            max_technique: None,
+            attempts: 100,
+            minimal: false,
+        }
+    }
+}
+
+/// Puzzle made by [generate]. Serialized as {"puzzle", "steps"}
+#[derive(Clone, Debug, Serialize)]
+pub struct GeneratedPuzzle<const SIZE: usize, const BLOCK_SIZE: usize> {
+    pub puzzle: SudokuBoard<SIZE, BLOCK_SIZE>,
+    /// Steps of the solution without guessing, when the options ask for techniques, which show
+    /// where each one is needed
+    pub steps: Vec<ReportStep<SIZE, BLOCK_SIZE>>,
+}
+
+/// Generate a puzzle with a unique solution, removing the numbers of a random solution in
+/// symmetric groups of cells while the solution stays unique and there are more givens than the
+/// coverage asks for. When the options ask for techniques, each removal must keep the puzzle
+/// solvable with them. The checks see nothing but the numbers, like anyone reading the literal of
+/// the puzzle. Return None if no attempt needs the required technique
+pub fn generate<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(options: &GeneratorOptions, rand: &mut R) -> Option<GeneratedPuzzle<SIZE, BLOCK_SIZE>> {
+    let logical = options.required_technique.is_some() || options.max_technique.is_some();
+    let max = options.max_technique.unwrap_or(Technique::XWing);
+    let needs_required = |steps: &[ReportStep<SIZE, BLOCK_SIZE>]| options.required_technique
+        .is_none_or(|required| steps.iter().any(|o| o.message.technique() == Some(required)));
+
+    for _ in 0..options.attempts.max(1) {
+        let mut result = random_board::<SIZE, BLOCK_SIZE, R>(1.0, rand);
+        let mut steps = Vec::new();
+        let mut solver = SudokuSolver::new(0);
+        let target = if options.minimal { 0 } else { (options.coverage * (SIZE * SIZE) as f64).round() as usize };
+        let mut givens = SIZE * SIZE;
+
+        let mut orbits = options.symmetry.orbits::<SIZE>();
+        orbits.shuffle(rand);
+        // A whole orbit may not be removable when some of its cells are
+        if options.minimal && options.symmetry != Symmetry::None {
+            let mut cells = Symmetry::None.orbits::<SIZE>();
+            cells.shuffle(rand);
+            orbits.extend(cells);
+        }
+
+        for orbit in orbits {
+            if givens <= target || (!options.minimal && options.required_technique.is_some() && needs_required(&steps)) {
+                break;
+            }
+            if orbit.iter().any(|&[row, col]| result.get_number(row, col).is_none()) {
+                continue;
+            }
+
+            let mut removed = result.clone();
+            for &[row, col] in &orbit {
+                removed.set_number(None, row, col);
+            }
+
+            if logical {
+                let Some(solution) = SudokuSolver::solve_logically(&removed, max) else { continue; };
+                steps = solution.steps;
+            } else if solver.count_solutions(&removed, 2) != 1 {
+                continue;
+            }
+            result = removed;
+            givens -= orbit.len();
+        }
+
+        if logical && steps.is_empty() {
+            steps = SudokuSolver::solve_logically(&result, max)?.steps;
+        }
+        if needs_required(&steps) {
+            return Some(GeneratedPuzzle { puzzle: result, steps });
+        }
+    }
+
+    None
+}
+
+/// Generate a puzzle of the difficulty, trying up to the number of attempts. Expert puzzles are
+/// minimal, since they are the most likely to need guessing
+pub fn generate_graded<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(difficulty: Difficulty, symmetry: Symmetry, attempts: usize,
+                                                                       rand: &mut R) -> Option<GeneratedPuzzle<SIZE, BLOCK_SIZE>> {
+    let max = difficulty.max_technique();
+    let options = GeneratorOptions {
+        // Removing as many givens as the techniques allow
+        coverage: 0.0,
+        symmetry,
+        // The easiest band can't need anything else, and the others must need their hardest technique
+        required_technique: max.filter(|&o| o != Technique::SoleCandidate),
+        max_technique: max,
+        attempts: 1,
+        minimal: difficulty == Difficulty::Expert,
+    };
+
+    for _ in 0..attempts {
+        let Some(generated) = generate(&options, rand) else { continue; };
+        if grade(&generated.puzzle).0 == difficulty {
+            return Some(generated);
+        }
+    }
+    None
+}
+
+/// Search for a puzzle with a unique solution whose givens are exactly the cells of the mask.
+/// It starts from the numbers of a random solution in the mask, and each step changes one of them,
+/// keeping the change when the puzzle still has solutions, but not more than before. After a number
+/// of steps equal to the budget without a unique solution, it starts over from another random
+/// solution, until the attempts run out. Return None in that case. Solutions are counted with
+/// nothing but the numbers, so the literal of the puzzle has the same unique solution
+pub fn generate_from_mask<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(mask: &Array2D<bool, SIZE>, attempts: usize,
+                                                                          budget: usize, rand: &mut R) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    // Solutions are only counted up to this, so very ambiguous puzzles all look the same
+    const COUNT_LIMIT: usize = 50;
+    let cells: Vec<[usize; 2]> = (0..SIZE)
+        .flat_map(|row| (0..SIZE).map(move |col| [row, col]))
+        .filter(|&[row, col]| mask[row][col])
+        .collect();
+    if cells.is_empty() {
+        return None;
+    }
+    let mut solver = SudokuSolver::new(0);
+
+    for _ in 0..attempts {
+        let mut result = random_board::<SIZE, BLOCK_SIZE, R>(1.0, rand);
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if !mask[row][col] {
+                    result.set_number(None, row, col);
+                }
+            }
+        }
+        let mut count = solver.count_solutions(&result, COUNT_LIMIT);
+
+        for _ in 0..budget {
+            if count == 1 {
+                return Some(result);
+            }
+
+            let [row, col] = cells[rand.gen_range(0..cells.len() as u32) as usize];
+            let mut changed = result.clone();
+            let current = changed.get_number(row, col).unwrap();
+            changed.set_number(None, row, col);
+            let mut options = changed.get_possible(row, col);
+            options.remove_number(current);
+            if options.count() == 0 { continue; }
+
+            let options = options.as_vec();
+            changed.set_number(Some(options[rand.gen_range(0..options.len() as u32) as usize]), row, col);
+            let changed_count = solver.count_solutions(&changed, COUNT_LIMIT);
+            if changed_count >= 1 && changed_count <= count {
+                result = changed;
+                count = changed_count;
+            }
+        }
+        if count == 1 {
+            return Some(result);
+        }
+    }
+    None
+}
+
+/// Read a mask for [generate_from_mask], where each cell is "x" for a given or any other character
+/// for an empty cell, like "x...x....". Whitespace is ignored
+pub fn clue_mask_from_literal<const SIZE: usize>(literal: &str) -> Option<Array2D<bool, SIZE>> {
+    let cells: Vec<bool> = literal.chars()
+        .filter(|o| !o.is_whitespace())
+        .map(|o| o.eq_ignore_ascii_case(&'x'))
+        .collect();
+    if cells.len() != SIZE * SIZE {
+        return None;
+    }
+
+    let mut result = [[false; SIZE]; SIZE];
+    for (index, given) in cells.into_iter().enumerate() {
+        result[index / SIZE][index % SIZE] = given;
+    }
+    Some(result)
+}
+
+/// Return the givens that can be removed, one at a time, keeping a unique solution. Numbers placed
+/// by the player or the solver are ignored, and so are the candidates removed from the cells,
+/// which the literal of the puzzle doesn't show. Empty if the givens don't have a unique solution
+/// to begin with
+pub fn redundant_givens<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> Vec<[usize; 2]> {
+    let board = &board.givens();
+    let mut solver = SudokuSolver::new(0);
+    if solver.count_solutions(board, 2) != 1 {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            if board.get_number(row, col).is_none() { continue; }
+
+            let mut removed = board.clone();
+            removed.set_number(None, row, col);
+            if solver.count_solutions(&removed, 2) == 1 {
+                result.push([row, col]);
+            }
+        }
+    }
+    result
+}
+
+/// Whether the givens of the board have a unique solution that removing any of them would break
+pub fn is_minimal<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> bool {
+    SudokuSolver::new(0).count_solutions(&board.givens(), 2) == 1 && redundant_givens(board).is_empty()
+}
+
+/// Generate a puzzle from a random solution, keeping each number as a given with a chance of
+/// coverage. Seeded generators, like [rand_chacha::ChaCha8Rng], give the same puzzle on every
+/// platform, so numbers are only drawn as u32 and never as usize
+pub fn random_board<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(coverage: f64, rand: &mut R) -> SudokuBoard<SIZE, BLOCK_SIZE> {
+    let mut board = SudokuBoard::<SIZE, BLOCK_SIZE>::new();
+    board.set_number(Some(rand.gen_range(1..=SIZE as u32) as u8),
+                     rand.gen_range(0..SIZE as u32) as usize, rand.gen_range(0..SIZE as u32) as usize);
+
+    random_board_with_rules(&board, coverage, rand).unwrap()
+}
+
+/// Same as [random_board], for a board with the rules of a variant, like anti-knight, and maybe
+/// some numbers that the solution must have. Return None if no solution follows the rules
+pub fn random_board_with_rules<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, coverage: f64,
+                                                                                   rand: &mut R) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    // Numbers that already break the rules would only be noticed once the board is full, after
+    // trying every way to fill the other cells
+    if board.find_error().is_some() {
+        return None;
+    }
+    let mut solver = SudokuSolver::new(0);
+    let mut result = solver.solve_random(board, rand)?;
+    result.mark_all_as_given();
+    // The candidates removed while solving would stay hidden in the puzzle, so only the numbers
+    // are kept, like in its literal
+    let mut result = result.givens();
+
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            if !rand.gen_bool(coverage) {
+                result.set_number(None, row, col);
+            }
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use crate::generator::{clue_mask_from_literal, generate, generate_from_mask, generate_graded, grade, is_minimal, random_board, redundant_givens,
+                           random_board_with_rules, Difficulty, GeneratorOptions, Symmetry};
+    use crate::number_options::NumberOptions;
+    use crate::solve_report::Technique;
+    use crate::sudoku_board::{DefaultBoard, SudokuBoard};
+    use crate::sudoku_examples::{EASY_LITERALS, HARD_LITERALS};
+    use crate::sudoku_solver::SudokuSolver;
+
+    #[test]
+    fn seeded_generation() {
+        let first = random_board::<9, 3, _>(0.4, &mut ChaCha8Rng::seed_from_u64(20));
+        let second = random_board::<9, 3, _>(0.4, &mut ChaCha8Rng::seed_from_u64(20));
+        let other = random_board::<9, 3, _>(0.4, &mut ChaCha8Rng::seed_from_u64(21));
+        assert_eq!(first.to_literal(), second.to_literal());
+        assert_ne!(first.to_literal(), other.to_literal());
+        assert!(first.find_error().is_none());
+
+        let small: SudokuBoard<4, 2> = random_board(1.0, &mut ChaCha8Rng::seed_from_u64(20));
+        assert!(small.is_full() && small.find_error().is_none());
+
+        // Fixed output, so changes to the generator or to the random numbers it draws are noticed
+        let puzzle = random_board::<4, 2, _>(0.5, &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(puzzle.to_literal(), "_ _ _ _ _ _ _ _ 4 _ _ 3 1 3 4 2 ");
+    }
+
+    #[test]
+    fn no_hidden_candidates() {
+        for seed in 0..50 {
+            let solution = random_board::<9, 3, _>(1.0, &mut ChaCha8Rng::seed_from_u64(seed));
+            assert!((0..81).all(|o| solution.get_allowed(o / 9, o % 9).all()));
+        }
+
+        // The puzzle has a unique solution with nothing but the numbers of its literal
+        for seed in [24, 40] {
+            let options = GeneratorOptions { coverage: 0.0, ..GeneratorOptions::default() };
+            let puzzle = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap().puzzle;
+            assert_eq!(SudokuSolver::new(0).count_solutions(&DefaultBoard::from_literal(&puzzle.to_literal()), 2), 1);
+        }
+    }
+
+    #[test]
+    fn variant_random_board() {
+        let mut board = DefaultBoard::new();
+        board.rules_mut().set_anti_knight(true);
+        let solution = random_board_with_rules(&board, 1.0, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+        assert!(solution.is_full() && solution.find_error().is_none());
+        assert!(solution.rules().is_anti_knight());
+
+        let puzzle = random_board_with_rules(&board, 0.5, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+        assert!(!puzzle.is_full() && puzzle.rules().is_anti_knight());
+
+        // Two 1s a knight's move apart break the rule
+        board.set_number(Some(1), 0, 2);
+        board.set_number(Some(1), 1, 4);
+        assert!(random_board_with_rules(&board, 0.5, &mut ChaCha8Rng::seed_from_u64(3)).is_none());
+    }
+
+    #[test]
+    fn orbits() {
+        assert_eq!(Symmetry::Rotate90.orbit::<9>(0, 1), vec![[0, 1], [1, 8], [7, 0], [8, 7]]);
+        assert_eq!(Symmetry::Rotate180.orbit::<9>(4, 4), vec![[4, 4]]);
+        assert_eq!(Symmetry::Diagonal.orbit::<9>(2, 5), vec![[2, 5], [5, 2]]);
+
+        for symmetry in [Symmetry::None, Symmetry::Rotate180, Symmetry::Rotate90, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal] {
+            let orbits = symmetry.orbits::<9>();
+            assert_eq!(orbits.iter().map(Vec::len).sum::<usize>(), 81);
+        }
+    }
+
+    #[test]
+    fn symmetric_generation() {
+        let mut solver = SudokuSolver::new(0);
+        for symmetry in [Symmetry::Rotate180, Symmetry::Rotate90, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal] {
+            let options = GeneratorOptions { coverage: 0.35, symmetry, ..GeneratorOptions::default() };
+            let puzzle = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(7)).unwrap().puzzle;
+            assert_eq!(solver.count_solutions(&DefaultBoard::from_literal(&puzzle.to_literal()), 2), 1);
+
+            for row in 0..9 {
+                for col in 0..9 {
+                    let given = puzzle.get_number(row, col).is_some();
+                    assert!(symmetry.orbit::<9>(row, col).iter().all(|&[r, c]| puzzle.get_number(r, c).is_some() == given));
+                }
+            }
+        }
+
+        let options: GeneratorOptions = serde_json::from_str(r#"{"symmetry": "rotate180"}"#).unwrap();
+        assert_eq!(options, GeneratorOptions { symmetry: Symmetry::Rotate180, ..GeneratorOptions::default() });
+    }
+
+    #[test]
+    fn technique_generation() {
+        let options = GeneratorOptions {
+            coverage: 0.2,
+            required_technique: Some(Technique::XWing),
+            max_technique: Some(Technique::XWing),
+            ..GeneratorOptions::default()
+        };
+        let generated = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+        assert!(generated.steps.iter().any(|o| o.message.technique() == Some(Technique::XWing)));
+        assert!(generated.steps.iter().all(|o| o.message.technique().is_some()));
+        assert_eq!(SudokuSolver::new(0).count_solutions(&generated.puzzle, 2), 1);
+
+        // Plain solving doesn't use X-Wing
+        let mut solver = SudokuSolver::new(1000);
+        assert!(solver.solve(&generated.puzzle).is_some());
+        assert!(solver.steps.iter().all(|o| o.message.technique() != Some(Technique::XWing)));
+
+        let options = GeneratorOptions { max_technique: Some(Technique::SoleCandidate), ..GeneratorOptions::default() };
+        let generated = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+        assert!(generated.steps.iter().all(|o| o.message.technique() == Some(Technique::SoleCandidate)));
+    }
+
+    #[test]
+    fn minimality() {
+        // A full board has more givens than needed
+        let mut solution = SudokuSolver::new(0).solve(&DefaultBoard::from_literal(EASY_LITERALS[0])).unwrap();
+        let puzzle = solution.givens();
+        solution.mark_all_as_given();
+        assert!(!is_minimal(&solution));
+        assert_eq!(redundant_givens(&solution).len(), 81);
+        assert!(redundant_givens(&DefaultBoard::new()).is_empty());
+
+        // Numbers that aren't givens can't be removed from the puzzle
+        let mut progress = puzzle.clone();
+        progress.set_user_number(solution.get_number(0, 0), 0, 0);
+        assert_eq!(redundant_givens(&progress), redundant_givens(&puzzle));
+        assert!(!redundant_givens(&progress).contains(&[0, 0]));
+
+        // Neither can the candidates that only a solver knows were removed
+        let mut restricted = puzzle.clone();
+        for col in 0..9 {
+            restricted.restrict(0, col, NumberOptions::single(solution.get_number(0, col).unwrap()));
+        }
+        assert_eq!(redundant_givens(&restricted), redundant_givens(&puzzle));
+
+        for symmetry in [Symmetry::None, Symmetry::Rotate180] {
+            let options = GeneratorOptions { symmetry, minimal: true, ..GeneratorOptions::default() };
+            let puzzle = generate::<9, 3, _>(&options, &mut ChaCha8Rng::seed_from_u64(5)).unwrap().puzzle;
+            assert!(is_minimal(&DefaultBoard::from_literal(&puzzle.to_literal())));
+        }
+    }
+
+    #[test]
+    fn mask_generation() {
+        // A heart
+        let mask = clue_mask_from_literal::<9>("
+            .xx...xx.
+            x..x.x..x
+            x...x...x
+            x.......x
+            .x.....x.
+            ..x...x..
+            ...x.x...
+            ....x....
+            .xxxxxxx.
+        ").unwrap();
+        let puzzle = generate_from_mask::<9, 3, _>(&mask, 10, 1000, &mut ChaCha8Rng::seed_from_u64(1)).unwrap();
+        assert_eq!(SudokuSolver::new(0).count_solutions(&puzzle, 2), 1);
+        for row in 0..9 {
+            for col in 0..9 {
+                assert_eq!(puzzle.get_number(row, col).is_some(), mask[row][col]);
+            }
+        }
+
+        // Too few givens for a unique solution
+        let mask = clue_mask_from_literal::<9>(&format!("xxxx{}", ".".repeat(77))).unwrap();
+        assert!(generate_from_mask::<9, 3, _>(&mask, 2, 20, &mut ChaCha8Rng::seed_from_u64(1)).is_none());
+        assert!(clue_mask_from_literal::<9>("x..").is_none());
+    }
+
+    #[test]
+    fn graded_generation() {
+        assert_eq!(grade(&DefaultBoard::from_literal(HARD_LITERALS[0])), (Difficulty::Medium, Some(Technique::UniqueCandidate)));
+        assert_eq!(grade(&DefaultBoard::new()), (Difficulty::Expert, None));
+
+        let mut rand = ChaCha8Rng::seed_from_u64(2);
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert] {
+            let generated = generate_graded::<9, 3, _>(difficulty, Symmetry::Rotate180, 100, &mut rand).unwrap();
+            let puzzle = DefaultBoard::from_literal(&generated.puzzle.to_literal());
+            assert_eq!(grade(&puzzle).0, difficulty);
+            assert_eq!(SudokuSolver::new(0).count_solutions(&puzzle, 2), 1);
+        }
+    }
+}
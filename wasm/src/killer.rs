@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use crate::number_options::NumberOptions;
+use crate::util::{cell_from_literal, cell_to_literal};
+
+/// Group of cells in a Killer Sudoku whose numbers must add up to the sum, without repeating.
+/// Cells are [row, col], 0-based
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Cage {
+    pub sum: u16,
+    pub cells: Vec<[usize; 2]>,
+}
+
+impl Cage {
+    pub fn new(sum: u16, cells: Vec<[usize; 2]>) -> Self {
+        Cage { sum, cells }
+    }
+
+    /// Read a cage in the format "12: r1c1 r1c2 r2c1", where rows and columns are 1-based
+    pub fn from_literal(literal: &str) -> Option<Self> {
+        let (sum, cells) = literal.split_once(':')?;
+        let sum = sum.trim().parse().ok()?;
+
+        let cells = cells.split_whitespace()
+            .map(cell_from_literal)
+            .collect::<Option<Vec<[usize; 2]>>>()?;
+
+        if cells.is_empty() {
+            return None;
+        }
+        Some(Cage::new(sum, cells))
+    }
+
+    /// Write the cage in the format "12: r1c1 r1c2 r2c1"
+    pub fn to_literal(&self) -> String {
+        let cells: Vec<String> = self.cells.iter().copied().map(cell_to_literal).collect();
+        format!("{}: {}", self.sum, cells.join(" "))
+    }
+
+    /// Read a list of cages, one per line. Return None if any line is invalid
+    pub fn list_from_literal(literal: &str) -> Option<Vec<Self>> {
+        literal.lines()
+            .filter(|o| !o.trim().is_empty())
+            .map(Cage::from_literal)
+            .collect()
+    }
+
+    /// The top left cell of the cage, used to name it in messages
+    pub fn first_cell(&self) -> [usize; 2] {
+        *self.cells.iter().min().unwrap()
+    }
+}
+
+/// Return every set of `count` different numbers among the options that add up to the sum
+pub fn sum_combinations<const SIZE: usize>(sum: u16, count: usize, options: NumberOptions<SIZE>) -> Vec<NumberOptions<SIZE>> {
+    let mut result = Vec::new();
+    add_combinations(sum, count, &options.as_vec(), NumberOptions::default(), &mut result);
+    result
+}
+
+fn add_combinations<const SIZE: usize>(sum: u16, count: usize, options: &[u8], current: NumberOptions<SIZE>,
+                                       result: &mut Vec<NumberOptions<SIZE>>) {
+    if count == 0 {
+        if sum == 0 {
+            result.push(current);
+        }
+        return;
+    }
+
+    for (index, &num) in options.iter().enumerate() {
+        // The options are sorted, so no later number fits either
+        if num as u16 > sum { break; }
+
+        let mut next = current;
+        next.add_number(num);
+        add_combinations(sum - num as u16, count - 1, &options[index + 1..], next, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::killer::{Cage, sum_combinations};
+    use crate::number_options::NumberOptions;
+
+    #[test]
+    fn combinations() {
+        let result: Vec<Vec<u8>> = sum_combinations::<9>(12, 2, NumberOptions::full())
+            .iter().map(NumberOptions::as_vec).collect();
+        assert_eq!(result, vec![vec![3, 9], vec![4, 8], vec![5, 7]]);
+
+        assert_eq!(sum_combinations::<9>(45, 9, NumberOptions::full()).len(), 1);
+        assert!(sum_combinations::<9>(4, 2, NumberOptions::full()).iter().all(|o| o.as_vec() == vec![1, 3]));
+    }
+
+    #[test]
+    fn cage_literal() {
+        let cage = Cage::from_literal("12: r1c1 r1c2 r2c1").unwrap();
+        assert_eq!(cage, Cage::new(12, vec![[0, 0], [0, 1], [1, 0]]));
+        assert_eq!(cage.to_literal(), "12: r1c1 r1c2 r2c1");
+
+        assert!(Cage::from_literal("12: r0c1").is_none());
+        assert!(Cage::from_literal("12 r1c1").is_none());
+    }
+}
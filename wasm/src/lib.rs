@@ -1,16 +1,34 @@
 pub mod sudoku_board;
 pub mod sudoku_examples;
 pub mod sudoku_solver;
-mod number_options;
-mod util;
-mod solve_report;
+pub mod number_options;
+pub mod util;
+pub mod solve_report;
+pub mod progress;
+pub mod formats;
+pub mod rules;
+pub mod killer;
+pub mod lines;
+pub mod pairs;
+pub mod outside;
+pub mod multi_grid;
+pub mod generator;
+pub mod transform;
+pub mod canonical;
+pub mod analysis;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod corpus;
+pub mod constraint;
 
-use instant::Instant;
-use json::{array, JsonValue, object};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
 use wasm_bindgen::prelude::*;
-use crate::solve_report::Message;
-use crate::sudoku_board::{BoardError, SudokuBoard};
+use crate::formats::PuzzleFormat;
+use crate::generator::GeneratorOptions;
+use crate::multi_grid::{Layout, MultiGrid};
+use crate::progress::check_progress;
+use crate::sudoku_board::SudokuBoard;
 use crate::sudoku_solver::SudokuSolver;
 
 #[cfg(wasm_alloc)]
@@ -35,36 +53,49 @@ pub fn solve(board_literal: &str, block_size: usize, record_steps: usize) -> Str
 fn solve_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_literal: &str, record_steps: usize) -> String {
     let board = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(board_literal);
     let mut solver = SudokuSolver::new(record_steps);
-    let start = Instant::now();
-    let result = solver.solve(&board);
-    let elapsed = start.elapsed().as_micros();
+    let result = solver.solve_with_result(&board);
 
-    if result.is_none() {
-        return JsonValue::Null.dump();
+    serde_json::to_string(&result.map(|o| o.steps)).expect("Invalid Json object")
+}
+
+/// Same as [solve], but reads the board in the JSON format of [SudokuBoard], which also
+/// describes the rules of variant puzzles, like Killer Sudoku cages
+#[wasm_bindgen]
+pub fn solve_json(board_json: &str, block_size: usize, record_steps: usize) -> String {
+    match block_size {
+        2 => solve_json_with_size::<4, 2>(board_json, record_steps),
+        3 => solve_json_with_size::<9, 3>(board_json, record_steps),
+        4 => solve_json_with_size::<16, 4>(board_json, record_steps),
+        _ => panic!("Invalid size")
     }
+}
 
-    let mut steps = JsonValue::new_array();
+fn solve_json_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_json: &str, record_steps: usize) -> String {
+    let board: SudokuBoard<SIZE, BLOCK_SIZE> = serde_json::from_str(board_json).expect("Invalid board");
+    let mut solver = SudokuSolver::new(record_steps);
+    let result = solver.solve_with_result(&board);
 
-    for step in solver.steps {
-        steps.push(object! {
-            message: step.message.into_object(),
-            highlightRow: step.highlight_row,
-            highlightCol: step.highlight_col,
-            highlightBlock: step.highlight_block.map(|[a, b]| array![a, b]),
-            literal: step.literal,
-        }).expect("Invalid Json object");
-    }
+    serde_json::to_string(&result.map(|o| o.steps)).expect("Invalid Json object")
+}
 
-    let solution = result.unwrap().to_literal();
-    steps.push(object! {
-        message: Message::Found(elapsed as u64).into_object(),
-        highlightRow: JsonValue::Null,
-        highlightCol: JsonValue::Null,
-        highlightBlock: JsonValue::Null,
-        literal: solution
-    }).expect("Invalid Json object");
+/// Solve a 9x9 multi-grid puzzle written like [MultiGrid::to_literal], with the grids arranged in a
+/// layout named "samurai", "butterfly" or "twodoku". Return the solution in the same format, or
+/// null if there's none
+#[wasm_bindgen]
+pub fn solve_multi_grid(puzzle_literal: &str, layout: &str) -> String {
+    let layout = Layout::from_name(layout).expect("Invalid layout");
+    let puzzle = MultiGrid::<9, 3>::from_literal(layout.offsets::<9, 3>(), puzzle_literal).expect("Invalid puzzle");
+    let result = SudokuSolver::new(0).solve_multi_grid(&puzzle);
+    serde_json::to_string(&result.map(|o| o.to_literal())).expect("Invalid Json object")
+}
 
-    steps.dump()
+/// Same as [solve_multi_grid], but reads and writes the puzzle in the JSON format of [MultiGrid],
+/// which can have any layout and variant rules in each grid
+#[wasm_bindgen]
+pub fn solve_multi_grid_json(puzzle_json: &str) -> String {
+    let puzzle: MultiGrid<9, 3> = serde_json::from_str(puzzle_json).expect("Invalid puzzle");
+    let result = SudokuSolver::new(0).solve_multi_grid(&puzzle);
+    serde_json::to_string(&result).expect("Invalid Json object")
 }
 
 #[wasm_bindgen]
@@ -79,44 +110,278 @@ pub fn find_errors(board_literal: &str, block_size: usize) -> String {
 
 fn find_errors_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_literal: &str) -> String {
     let result = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal_checked(board_literal);
-    match result {
-        Ok(_) => JsonValue::Null,
-        Err(error) => match error {
-            BoardError::RowError(row) => object! {type: "row", value: row},
-            BoardError::ColError(col) => object! {type: "col", value: col},
-            BoardError::BlockError(row, col) => object! {type: "block", value: array![row, col]}
-        }
-    }.dump()
+    serde_json::to_string(&result.err()).expect("Invalid Json object")
+}
+
+/// Same as [find_errors], but reads the board in the JSON format of [SudokuBoard]
+#[wasm_bindgen]
+pub fn find_errors_json(board_json: &str, block_size: usize) -> String {
+    match block_size {
+        2 => find_errors_json_with_size::<4, 2>(board_json),
+        3 => find_errors_json_with_size::<9, 3>(board_json),
+        4 => find_errors_json_with_size::<16, 4>(board_json),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn find_errors_json_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_json: &str) -> String {
+    let board: SudokuBoard<SIZE, BLOCK_SIZE> = serde_json::from_str(board_json).expect("Invalid board");
+    serde_json::to_string(&board.find_error()).expect("Invalid Json object")
+}
+
+#[wasm_bindgen]
+pub fn check_board_progress(original_literal: &str, current_literal: &str, block_size: usize) -> String {
+    match block_size {
+        2 => check_progress_with_size::<4, 2>(original_literal, current_literal),
+        3 => check_progress_with_size::<9, 3>(original_literal, current_literal),
+        4 => check_progress_with_size::<16, 4>(original_literal, current_literal),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn check_progress_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(original_literal: &str, current_literal: &str) -> String {
+    let original = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(original_literal);
+    let current = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(current_literal);
+
+    let result = check_progress(&original, &current).map(|report| json!({
+        "wrong": report.wrong_cells,
+        "solvable": report.solvable,
+        "unique": report.unique,
+        "solution": report.solution.to_literal(),
+    }));
+    serde_json::to_string(&result).expect("Invalid Json object")
+}
+
+#[wasm_bindgen]
+pub fn import_puzzle(text: &str, block_size: usize) -> String {
+    match block_size {
+        2 => import_with_size::<4, 2>(text),
+        3 => import_with_size::<9, 3>(text),
+        4 => import_with_size::<16, 4>(text),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn import_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(text: &str) -> String {
+    let result = match formats::import::<SIZE, BLOCK_SIZE>(text) {
+        Ok(imported) => json!({
+            "format": imported.format.name(),
+            "literal": imported.board.to_literal(),
+            "pencilMarks": imported.pencil_marks.map(|o| o.iter().flatten().copied().collect::<Vec<_>>()),
+        }),
+        Err(error) => json!({ "error": error }),
+    };
+    serde_json::to_string(&result).expect("Invalid Json object")
+}
+
+#[wasm_bindgen]
+pub fn export_puzzle(board_literal: &str, format: &str, block_size: usize) -> String {
+    let format = PuzzleFormat::from_name(format).expect("Invalid format");
+    match block_size {
+        2 => formats::export(&SudokuBoard::<4, 2>::from_literal(board_literal), format, None),
+        3 => formats::export(&SudokuBoard::<9, 3>::from_literal(board_literal), format, None),
+        4 => formats::export(&SudokuBoard::<16, 4>::from_literal(board_literal), format, None),
+        _ => panic!("Invalid size")
+    }
 }
 
 #[wasm_bindgen]
 pub fn random_board(coverage: f64, block_size: usize) -> String {
     match block_size {
-        2 => random_board_with_size::<4, 2>(coverage),
-        3 => random_board_with_size::<9, 3>(coverage),
-        4 => random_board_with_size::<16, 4>(coverage),
+        2 => random_board_with_size::<4, 2>(coverage, &mut rand::thread_rng()),
+        3 => random_board_with_size::<9, 3>(coverage, &mut rand::thread_rng()),
+        4 => random_board_with_size::<16, 4>(coverage, &mut rand::thread_rng()),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn random_board_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(coverage: f64, rand: &mut impl Rng) -> String {
+    generator::random_board::<SIZE, BLOCK_SIZE, _>(coverage, rand).to_literal()
+}
+
+/// Same as [random_board], but the same seed always gives the same puzzle, on every platform
+#[wasm_bindgen]
+pub fn random_board_seeded(seed: u32, coverage: f64, block_size: usize) -> String {
+    let mut rand = ChaCha8Rng::seed_from_u64(seed as u64);
+    match block_size {
+        2 => random_board_with_size::<4, 2>(coverage, &mut rand),
+        3 => random_board_with_size::<9, 3>(coverage, &mut rand),
+        4 => random_board_with_size::<16, 4>(coverage, &mut rand),
+        _ => panic!("Invalid size")
+    }
+}
+
+/// Same as [random_board], for a board in JSON with the rules of a variant, like
+/// {"size": 9, "blockSize": 3, "cells": [...], "antiKnight": true}, and maybe some numbers that the
+/// solution must have. Return the puzzle in JSON, or null if no solution follows the rules
+#[wasm_bindgen]
+pub fn random_board_json(board_json: &str, coverage: f64, seed: Option<u32>, block_size: usize) -> String {
+    match seed {
+        Some(seed) => random_board_json_with_rng(board_json, coverage, block_size, &mut ChaCha8Rng::seed_from_u64(seed as u64)),
+        None => random_board_json_with_rng(board_json, coverage, block_size, &mut rand::thread_rng()),
+    }
+}
+
+fn random_board_json_with_rng(board_json: &str, coverage: f64, block_size: usize, rand: &mut impl Rng) -> String {
+    match block_size {
+        2 => random_board_json_with_size::<4, 2>(board_json, coverage, rand),
+        3 => random_board_json_with_size::<9, 3>(board_json, coverage, rand),
+        4 => random_board_json_with_size::<16, 4>(board_json, coverage, rand),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn random_board_json_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_json: &str, coverage: f64, rand: &mut impl Rng) -> String {
+    let board: SudokuBoard<SIZE, BLOCK_SIZE> = serde_json::from_str(board_json).expect("Invalid board");
+    serde_json::to_string(&generator::random_board_with_rules(&board, coverage, rand)).expect("Invalid Json object")
+}
+
+/// Generate a puzzle with a unique solution, according to [generator::GeneratorOptions] in JSON.
+/// Return a [generator::GeneratedPuzzle] in JSON, or null if the options can't be met. Without a
+/// seed, each call gives a different puzzle
+#[wasm_bindgen]
+pub fn generate_board(options_json: &str, seed: Option<u32>, block_size: usize) -> String {
+    let options: GeneratorOptions = serde_json::from_str(options_json).expect("Invalid options");
+    match seed {
+        Some(seed) => generate_with_size(&options, block_size, &mut ChaCha8Rng::seed_from_u64(seed as u64)),
+        None => generate_with_size(&options, block_size, &mut rand::thread_rng()),
+    }
+}
+
+fn generate_with_size(options: &GeneratorOptions, block_size: usize, rand: &mut impl Rng) -> String {
+    match block_size {
+        2 => serde_json::to_string(&generator::generate::<4, 2, _>(options, rand)).expect("Invalid Json object"),
+        3 => serde_json::to_string(&generator::generate::<9, 3, _>(options, rand)).expect("Invalid Json object"),
+        4 => serde_json::to_string(&generator::generate::<16, 4, _>(options, rand)).expect("Invalid Json object"),
+        _ => panic!("Invalid size")
+    }
+}
+
+/// Generate a puzzle whose givens are exactly the cells marked with "x" in the mask, see
+/// [generator::generate_from_mask]. Return the puzzle literal, or an empty string if none was
+/// found in the attempts and steps given
+#[wasm_bindgen]
+pub fn generate_from_mask(mask_literal: &str, attempts: usize, budget: usize, seed: Option<u32>, block_size: usize) -> String {
+    match seed {
+        Some(seed) => generate_from_mask_with_rng(mask_literal, attempts, budget, block_size, &mut ChaCha8Rng::seed_from_u64(seed as u64)),
+        None => generate_from_mask_with_rng(mask_literal, attempts, budget, block_size, &mut rand::thread_rng()),
+    }
+}
+
+fn generate_from_mask_with_rng(mask_literal: &str, attempts: usize, budget: usize, block_size: usize, rand: &mut impl Rng) -> String {
+    match block_size {
+        2 => generate_from_mask_with_size::<4, 2>(mask_literal, attempts, budget, rand),
+        3 => generate_from_mask_with_size::<9, 3>(mask_literal, attempts, budget, rand),
+        4 => generate_from_mask_with_size::<16, 4>(mask_literal, attempts, budget, rand),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn generate_from_mask_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(mask_literal: &str, attempts: usize, budget: usize, rand: &mut impl Rng) -> String {
+    let mask = generator::clue_mask_from_literal::<SIZE>(mask_literal).expect("Invalid mask");
+    let result = generator::generate_from_mask::<SIZE, BLOCK_SIZE, _>(&mask, attempts, budget, rand);
+    result.map(|o| o.to_literal()).unwrap_or_default()
+}
+
+/// Apply a random combination of the transformations that keep a puzzle valid, like relabeling
+/// the numbers and swapping rows, see [transform::random_transform]. Return the new literal, or an
+/// empty string if the board has rules that can't be moved
+#[wasm_bindgen]
+pub fn transform_puzzle(board_literal: &str, seed: Option<u32>, block_size: usize) -> String {
+    match seed {
+        Some(seed) => transform_with_rng(board_literal, block_size, &mut ChaCha8Rng::seed_from_u64(seed as u64)),
+        None => transform_with_rng(board_literal, block_size, &mut rand::thread_rng()),
+    }
+}
+
+fn transform_with_rng(board_literal: &str, block_size: usize, rand: &mut impl Rng) -> String {
+    match block_size {
+        2 => transform::random_transform(&SudokuBoard::<4, 2>::from_literal(board_literal), rand).map(|o| o.to_literal()).unwrap_or_default(),
+        3 => transform::random_transform(&SudokuBoard::<9, 3>::from_literal(board_literal), rand).map(|o| o.to_literal()).unwrap_or_default(),
+        4 => transform::random_transform(&SudokuBoard::<16, 4>::from_literal(board_literal), rand).map(|o| o.to_literal()).unwrap_or_default(),
+        _ => panic!("Invalid size")
+    }
+}
+
+/// Return the information about the puzzle in the JSON format of [analysis::PuzzleInfo]
+#[wasm_bindgen]
+pub fn analyze(board_literal: &str, block_size: usize) -> String {
+    match block_size {
+        2 => analyze_with_size::<4, 2>(board_literal),
+        3 => analyze_with_size::<9, 3>(board_literal),
+        4 => analyze_with_size::<16, 4>(board_literal),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn analyze_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_literal: &str) -> String {
+    let board = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(board_literal);
+    serde_json::to_string(&analysis::analyze(&board)).expect("Invalid Json object")
+}
+
+/// Return the backdoors of at most max_size cells and the key guesses of the puzzle in the JSON
+/// format of [analysis::BackdoorReport]. Sizes above [analysis::MAX_BACKDOOR_SIZE] are searched as
+/// that size, since the sets to try grow too fast
+#[wasm_bindgen]
+pub fn find_backdoors(board_literal: &str, max_size: usize, block_size: usize) -> String {
+    match block_size {
+        2 => find_backdoors_with_size::<4, 2>(board_literal, max_size),
+        3 => find_backdoors_with_size::<9, 3>(board_literal, max_size),
+        4 => find_backdoors_with_size::<16, 4>(board_literal, max_size),
         _ => panic!("Invalid size")
     }
 }
 
-fn random_board_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(coverage: f64) -> String {
-    let mut rand = rand::thread_rng();
-    let mut board = SudokuBoard::<SIZE, BLOCK_SIZE>::new();
-    board.set_number(Some(rand.gen_range(1..=SIZE) as u8),
-                     rand.gen_range(0..SIZE), rand.gen_range(0..SIZE));
+fn find_backdoors_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_literal: &str, max_size: usize) -> String {
+    let board = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(board_literal);
+    let max_size = max_size.min(analysis::MAX_BACKDOOR_SIZE);
+    serde_json::to_string(&analysis::backdoor_report(&board, max_size)).expect("Invalid Json object")
+}
 
-    let mut solver = SudokuSolver::new(0);
-    let mut result = solver.solve_random(&board, &mut rand).unwrap();
+/// Return the minlex canonical form of the puzzle, see [canonical::canonical_form]. Return
+/// undefined for 16x16 boards, which have too many column orders to try
+#[wasm_bindgen]
+pub fn canonicalize(board_literal: &str, block_size: usize) -> Option<String> {
+    match block_size {
+        2 => Some(canonical::canonical_form(&SudokuBoard::<4, 2>::from_literal(board_literal)).to_literal()),
+        3 => Some(canonical::canonical_form(&SudokuBoard::<9, 3>::from_literal(board_literal)).to_literal()),
+        4 => None,
+        _ => panic!("Invalid size")
+    }
+}
 
-    for row in 0..SIZE {
-        for col in 0..SIZE {
-            if !rand.gen_bool(coverage) {
-                result.set_number(None, row, col);
-            }
-        }
+/// Whether one puzzle can be made from the other by relabeling, swapping rows and columns,
+/// transposing or rotating. Return undefined for 16x16 boards, like [canonicalize]
+#[wasm_bindgen]
+pub fn are_equivalent(a_literal: &str, b_literal: &str, block_size: usize) -> Option<bool> {
+    match block_size {
+        2 => Some(canonical::are_equivalent(&SudokuBoard::<4, 2>::from_literal(a_literal), &SudokuBoard::<4, 2>::from_literal(b_literal))),
+        3 => Some(canonical::are_equivalent(&SudokuBoard::<9, 3>::from_literal(a_literal), &SudokuBoard::<9, 3>::from_literal(b_literal))),
+        4 => None,
+        _ => panic!("Invalid size")
     }
+}
 
-    result.to_literal()
+/// Check whether the puzzle is minimal. Return {"unique", "redundant"}, where "redundant" lists
+/// the [row, col] of the givens that can be removed keeping a unique solution, so the puzzle is
+/// minimal when it's unique and that list is empty
+#[wasm_bindgen]
+pub fn check_minimality(board_literal: &str, block_size: usize) -> String {
+    match block_size {
+        2 => check_minimality_with_size::<4, 2>(board_literal),
+        3 => check_minimality_with_size::<9, 3>(board_literal),
+        4 => check_minimality_with_size::<16, 4>(board_literal),
+        _ => panic!("Invalid size")
+    }
+}
+
+fn check_minimality_with_size<const SIZE: usize, const BLOCK_SIZE: usize>(board_literal: &str) -> String {
+    let board = SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal(board_literal);
+    let result = json!({
+        "unique": SudokuSolver::new(0).count_solutions(&board, 2) == 1,
+        "redundant": generator::redundant_givens(&board),
+    });
+    serde_json::to_string(&result).expect("Invalid Json object")
 }
 
 #[test]
@@ -130,4 +395,56 @@ fn test_random_board_with_size() {
 
         assert!(result.is_some());
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_check_board_progress() {
+    let original = sudoku_examples::EASY_LITERALS[0];
+    // Player entries can have no suffix, like in literals written by hand
+    let current = original.replacen('_', "5", 1);
+    let report: serde_json::Value = serde_json::from_str(&check_board_progress(original, &current, 3)).unwrap();
+    assert_eq!(report["wrong"], json!([[0, 0]]));
+    assert_eq!(report["solvable"], json!(false));
+
+    let current = original.replacen('_', "8", 1);
+    let report: serde_json::Value = serde_json::from_str(&check_board_progress(original, &current, 3)).unwrap();
+    assert_eq!(report["wrong"], json!([]));
+    assert_eq!(report["solvable"], json!(true));
+}
+
+#[test]
+fn test_random_board_json() {
+    let board = r#"{"size": 4, "blockSize": 2, "cells": [[null, null, null, null], [null, null, null, null], [null, null, null, null], [null, null, null, null]], "antiKing": true}"#;
+    let result = random_board_json(board, 1.0, Some(2), 2);
+    // No 4x4 board has every pair of diagonal neighbours different
+    assert_eq!(result, "null");
+
+    let board = board.replace("antiKing", "antiKnight");
+    let puzzle: SudokuBoard<4, 2> = serde_json::from_str(&random_board_json(&board, 0.5, Some(2), 2)).unwrap();
+    assert!(puzzle.rules().is_anti_knight() && puzzle.find_error().is_none());
+
+    // The candidates the solver removed with the rules aren't pencil marks of the player
+    let empty = serde_json::to_string(&SudokuBoard::<9, 3>::new()).unwrap();
+    let board = empty.trim_end_matches('}').to_owned() + r#","cages":[{"sum":10,"cells":[[4,4],[4,5],[5,4]]}],"lines":[{"kind":"thermometer","cells":[[0,0],[1,1],[2,2],[3,3]]}]}"#;
+    for seed in 0..10 {
+        let json = random_board_json(&board, 0.3, Some(seed), 3);
+        let puzzle: SudokuBoard<9, 3> = serde_json::from_str(&json).unwrap();
+        assert!(puzzle.find_error().is_none() && !json.contains("allowed"));
+    }
+}
+
+#[test]
+fn test_generate_from_mask() {
+    // Every cell given, so any solution is a puzzle with a unique solution
+    let literal = generate_from_mask(&"x".repeat(16), 1, 10, Some(1), 2);
+    assert!(SudokuBoard::<4, 2>::from_literal(&literal).is_full());
+    assert!(!literal.starts_with('"'));
+
+    // The returned literal alone has a unique solution
+    let mask = ".xx...xx.x..x.x..xx...x...xx.......x.x.....x...x...x.....x.x.......x.....xxxxxxx.";
+    let literal = generate_from_mask(mask, 10, 1000, Some(1), 3);
+    assert_eq!(SudokuSolver::new(0).count_solutions(&SudokuBoard::<9, 3>::from_literal(&literal), 2), 1);
+
+    // No givens can't give a unique solution
+    assert_eq!(generate_from_mask(&".".repeat(16), 1, 10, Some(1), 2), "");
+}
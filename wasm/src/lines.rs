@@ -0,0 +1,218 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use crate::constraint::Constraint;
+use crate::number_options::NumberOptions;
+use crate::solve_report::Message;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineKind {
+    /// Numbers strictly increase from the bulb, the first cell
+    Thermometer,
+    /// The circle, the first cell, contains the sum of the numbers along the rest of the line
+    Arrow,
+    /// The line reads the same in both directions
+    Palindrome,
+}
+
+/// Line drawn over the cells of a variant puzzle. Cells are [row, col], 0-based, in the order the
+/// line goes through them. Serialized as {"kind", "cells"}
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Line {
+    pub kind: LineKind,
+    pub cells: Vec<[usize; 2]>,
+}
+
+impl Line {
+    pub fn new(kind: LineKind, cells: Vec<[usize; 2]>) -> Self {
+        Line { kind, cells }
+    }
+
+    pub fn thermometer(cells: Vec<[usize; 2]>) -> Self {
+        Line::new(LineKind::Thermometer, cells)
+    }
+
+    /// Arrow whose circle is the first cell
+    pub fn arrow(cells: Vec<[usize; 2]>) -> Self {
+        Line::new(LineKind::Arrow, cells)
+    }
+
+    pub fn palindrome(cells: Vec<[usize; 2]>) -> Self {
+        Line::new(LineKind::Palindrome, cells)
+    }
+
+    fn is_thermometer_broken<const SIZE: usize>(numbers: &[Option<u8>]) -> bool {
+        let len = numbers.len();
+        for (i, a) in numbers.iter().enumerate() {
+            let Some(a) = *a else { continue; };
+            // The cells before and after need room for smaller and bigger numbers
+            if (a as usize) < i + 1 || a as usize + (len - 1 - i) > SIZE {
+                return true;
+            }
+
+            for (j, b) in numbers.iter().enumerate().skip(i + 1) {
+                if let Some(b) = *b {
+                    if (b as usize) < a as usize + (j - i) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn is_arrow_broken<const SIZE: usize>(numbers: &[Option<u8>]) -> bool {
+        let filled_sum: usize = numbers[1..].iter().flatten().map(|&o| o as usize).sum();
+        let empty = numbers[1..].iter().filter(|o| o.is_none()).count();
+
+        match numbers[0] {
+            Some(circle) => filled_sum + empty > circle as usize || (empty == 0 && filled_sum != circle as usize),
+            None => filled_sum + empty > SIZE,
+        }
+    }
+
+    fn is_palindrome_broken(numbers: &[Option<u8>]) -> bool {
+        let len = numbers.len();
+        (0..len / 2).any(|i| matches!((numbers[i], numbers[len - 1 - i]), (Some(a), Some(b)) if a != b))
+    }
+
+    fn restrict_thermometer<const SIZE: usize>(options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        let len = options.len();
+        let mut low = vec![0; len];
+        let mut high = vec![SIZE as i32 + 1; len];
+
+        let mut previous = 0;
+        for i in 0..len {
+            previous = match (options[i] & NumberOptions::range(previous + 1, SIZE as i32)).first() {
+                Some(num) => num as i32,
+                None => return vec![NumberOptions::default(); len],
+            };
+            low[i] = previous;
+        }
+
+        let mut next = SIZE as i32 + 1;
+        for i in (0..len).rev() {
+            next = match (options[i] & NumberOptions::range(1, next - 1)).last() {
+                Some(num) => num as i32,
+                None => return vec![NumberOptions::default(); len],
+            };
+            high[i] = next;
+        }
+
+        (0..len).map(|i| options[i] & NumberOptions::range(low[i], high[i])).collect()
+    }
+
+    fn restrict_arrow<const SIZE: usize>(options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        if options.iter().any(NumberOptions::empty) {
+            return options.to_vec();
+        }
+
+        let path = &options[1..];
+        let path_min: i32 = path.iter().map(|o| o.first().unwrap() as i32).sum();
+        let path_max: i32 = path.iter().map(|o| o.last().unwrap() as i32).sum();
+
+        let circle = options[0] & NumberOptions::range(path_min, path_max);
+        let mut result = vec![circle];
+        let (Some(circle_min), Some(circle_max)) = (circle.first(), circle.last()) else {
+            return vec![NumberOptions::default(); options.len()];
+        };
+
+        // Every cell gets what's left of the circle after the others take the most/least they can
+        for option in path {
+            let min = option.first().unwrap() as i32;
+            let max = option.last().unwrap() as i32;
+            let range = NumberOptions::range(circle_min as i32 - (path_max - max), circle_max as i32 - (path_min - min));
+            result.push(*option & range);
+        }
+        result
+    }
+
+    fn restrict_palindrome<const SIZE: usize>(options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        let len = options.len();
+        (0..len).map(|i| options[i] & options[len - 1 - i]).collect()
+    }
+}
+
+impl<const SIZE: usize> Constraint<SIZE> for Line {
+    fn cells(&self) -> Cow<'_, [[usize; 2]]> {
+        Cow::Borrowed(&self.cells)
+    }
+
+    fn is_broken(&self, numbers: &[Option<u8>]) -> bool {
+        match self.kind {
+            LineKind::Thermometer => Self::is_thermometer_broken::<SIZE>(numbers),
+            LineKind::Arrow => Self::is_arrow_broken::<SIZE>(numbers),
+            LineKind::Palindrome => Self::is_palindrome_broken(numbers),
+        }
+    }
+
+    fn restrict(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        match self.kind {
+            LineKind::Thermometer => Self::restrict_thermometer(options),
+            LineKind::Arrow => Self::restrict_arrow(options),
+            LineKind::Palindrome => Self::restrict_palindrome(options),
+        }
+    }
+
+    fn message(&self) -> Message {
+        let [row, col] = self.cells[0];
+        let (row, col) = (row + 1, col + 1);
+        match self.kind {
+            LineKind::Thermometer => Message::ThermometerIncreases { row, col },
+            LineKind::Arrow => Message::ArrowSum { row, col },
+            LineKind::Palindrome => Message::PalindromeMirrors { row, col },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraint::Constraint;
+    use crate::lines::Line;
+    use crate::number_options::NumberOptions;
+
+    fn options(nums: &[&[u8]]) -> Vec<NumberOptions<9>> {
+        nums.iter()
+            .map(|o| o.iter().fold(NumberOptions::default(), |acc, &num| acc | NumberOptions::single(num)))
+            .collect()
+    }
+
+    #[test]
+    fn thermometer() {
+        let line = Line::thermometer(vec![[0, 0], [0, 1], [0, 2]]);
+        assert!(!Constraint::<9>::is_broken(&line, &[Some(1), None, Some(3)]));
+        assert!(Constraint::<9>::is_broken(&line, &[Some(2), None, Some(3)]));
+        assert!(Constraint::<9>::is_broken(&line, &[None, Some(9), None]));
+
+        let full = NumberOptions::<9>::full();
+        let result = line.restrict(&[full, full, full]);
+        assert_eq!(result, options(&[&[1, 2, 3, 4, 5, 6, 7], &[2, 3, 4, 5, 6, 7, 8], &[3, 4, 5, 6, 7, 8, 9]]));
+
+        let result = line.restrict(&options(&[&[4, 8], &[1, 5, 9], &[2, 6, 9]]));
+        assert_eq!(result, options(&[&[4], &[5], &[6, 9]]));
+    }
+
+    #[test]
+    fn arrow() {
+        let line = Line::arrow(vec![[0, 0], [0, 1], [0, 2]]);
+        assert!(!Constraint::<9>::is_broken(&line, &[Some(5), Some(2), None]));
+        assert!(Constraint::<9>::is_broken(&line, &[Some(5), Some(2), Some(4)]));
+        assert!(Constraint::<9>::is_broken(&line, &[None, Some(6), Some(4)]));
+
+        let result = line.restrict(&options(&[&[3, 4], &[1, 2, 3, 9], &[1, 2]]));
+        assert_eq!(result, options(&[&[3, 4], &[1, 2, 3], &[1, 2]]));
+
+        let result = line.restrict(&options(&[&[1, 2, 3, 4, 5, 6, 7, 8, 9], &[1, 2], &[1, 2]]));
+        assert_eq!(result[0], options(&[&[2, 3, 4]])[0]);
+    }
+
+    #[test]
+    fn palindrome() {
+        let line = Line::palindrome(vec![[0, 0], [0, 1], [1, 1], [1, 0]]);
+        assert!(Constraint::<9>::is_broken(&line, &[Some(1), None, None, Some(2)]));
+        assert!(!Constraint::<9>::is_broken(&line, &[Some(1), Some(3), None, Some(1)]));
+
+        let result = line.restrict(&options(&[&[1, 2], &[5], &[4, 5], &[2, 3]]));
+        assert_eq!(result, options(&[&[2], &[5], &[5], &[2]]));
+    }
+}
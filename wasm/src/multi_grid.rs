@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use crate::formats::{cell_to_char, char_to_cell};
+use crate::number_options::NumberOptions;
+use crate::sudoku_board::{BoardError, CellOrigin, SudokuBoard};
+
+/// Common ways to arrange overlapping grids
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Layout {
+    /// Five grids, the center one sharing a corner block with each of the others
+    Samurai,
+    /// Four grids in a square, each overlapping the others
+    Butterfly,
+    /// Two grids sharing a corner block
+    Twodoku,
+}
+
+impl Layout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "samurai" => Some(Layout::Samurai),
+            "butterfly" => Some(Layout::Butterfly),
+            "twodoku" => Some(Layout::Twodoku),
+            _ => None,
+        }
+    }
+
+    /// Top left cell of each grid
+    pub fn offsets<const SIZE: usize, const BLOCK_SIZE: usize>(&self) -> Vec<[usize; 2]> {
+        let corner = SIZE - BLOCK_SIZE;
+        match self {
+            Layout::Samurai => vec![[0, 0], [0, SIZE + BLOCK_SIZE], [corner, corner], [SIZE + BLOCK_SIZE, 0], [SIZE + BLOCK_SIZE, SIZE + BLOCK_SIZE]],
+            Layout::Butterfly => vec![[0, 0], [0, BLOCK_SIZE], [BLOCK_SIZE, 0], [BLOCK_SIZE, BLOCK_SIZE]],
+            Layout::Twodoku => vec![[0, 0], [corner, corner]],
+        }
+    }
+}
+
+/// Error in one of the grids of a [MultiGrid]. Serialized as {"grid", "error"}
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MultiGridError {
+    pub grid: usize,
+    pub error: BoardError,
+}
+
+/// Puzzle made of grids that overlap in whole blocks, like a Samurai Sudoku. Cells shared by many
+/// grids always contain the same number in all of them. Rows and columns are counted from the top
+/// left corner of the whole puzzle.
+/// Serialized as {"offsets", "grids"}, where "offsets" has the top left [row, col] of each grid
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "MultiGridSchema<SIZE, BLOCK_SIZE>")]
+pub struct MultiGrid<const SIZE: usize, const BLOCK_SIZE: usize> {
+    offsets: Vec<[usize; 2]>,
+    grids: Vec<SudokuBoard<SIZE, BLOCK_SIZE>>,
+}
+
+#[derive(Deserialize)]
+struct MultiGridSchema<const SIZE: usize, const BLOCK_SIZE: usize> {
+    offsets: Vec<[usize; 2]>,
+    grids: Vec<SudokuBoard<SIZE, BLOCK_SIZE>>,
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> TryFrom<MultiGridSchema<SIZE, BLOCK_SIZE>> for MultiGrid<SIZE, BLOCK_SIZE> {
+    type Error = String;
+
+    fn try_from(schema: MultiGridSchema<SIZE, BLOCK_SIZE>) -> Result<Self, Self::Error> {
+        if schema.offsets.len() != schema.grids.len() {
+            return Err("Every grid must have an offset".to_owned());
+        }
+
+        let mut result = MultiGrid::new(schema.offsets)
+            .ok_or_else(|| "Offsets must be multiples of the block size".to_owned())?;
+        result.grids = schema.grids;
+
+        for [row, col] in result.cells() {
+            let mut numbers = result.grids_at(row, col).map(|(grid, r, c)| result.grids[grid].get_number(r, c));
+            let first = numbers.next().flatten();
+            if numbers.any(|o| o != first) {
+                return Err("Shared cells must contain the same number in every grid".to_owned());
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> MultiGrid<SIZE, BLOCK_SIZE> {
+    /// Create empty grids with their top left cells at the offsets. Return None if there are no
+    /// grids or an offset isn't a multiple of the block size
+    pub fn new(offsets: Vec<[usize; 2]>) -> Option<Self> {
+        if offsets.is_empty() || offsets.iter().flatten().any(|o| o % BLOCK_SIZE != 0) {
+            return None;
+        }
+
+        Some(MultiGrid {
+            grids: vec![SudokuBoard::new(); offsets.len()],
+            offsets,
+        })
+    }
+
+    pub fn from_layout(layout: Layout) -> Self {
+        Self::new(layout.offsets::<SIZE, BLOCK_SIZE>()).unwrap()
+    }
+
+    pub fn offsets(&self) -> &[[usize; 2]] {
+        &self.offsets
+    }
+
+    pub fn grids(&self) -> &[SudokuBoard<SIZE, BLOCK_SIZE>] {
+        &self.grids
+    }
+
+    pub fn grids_mut(&mut self) -> &mut [SudokuBoard<SIZE, BLOCK_SIZE>] {
+        &mut self.grids
+    }
+
+    pub fn height(&self) -> usize {
+        self.offsets.iter().map(|o| o[0] + SIZE).max().unwrap()
+    }
+
+    pub fn width(&self) -> usize {
+        self.offsets.iter().map(|o| o[1] + SIZE).max().unwrap()
+    }
+
+    /// Return the grids that contain a cell, as (index of the grid, row, col inside the grid)
+    pub fn grids_at(&self, row: usize, col: usize) -> impl Iterator<Item=(usize, usize, usize)> + '_ {
+        self.offsets.iter()
+            .enumerate()
+            .filter(move |(_, &[top, left])| row >= top && row < top + SIZE && col >= left && col < left + SIZE)
+            .map(move |(index, &[top, left])| (index, row - top, col - left))
+    }
+
+    /// Return the cells that belong to any grid, in reading order
+    pub fn cells(&self) -> Vec<[usize; 2]> {
+        let mut result = Vec::new();
+        for row in 0..self.height() {
+            for col in 0..self.width() {
+                if self.grids_at(row, col).next().is_some() {
+                    result.push([row, col]);
+                }
+            }
+        }
+        result
+    }
+
+    pub fn get_number(&self, row: usize, col: usize) -> Option<u8> {
+        let (grid, r, c) = self.grids_at(row, col).next()?;
+        self.grids[grid].get_number(r, c)
+    }
+
+    /// Set a cell in every grid that contains it
+    pub fn set_cell(&mut self, value: Option<u8>, origin: CellOrigin, row: usize, col: usize) {
+        let grids: Vec<_> = self.grids_at(row, col).collect();
+        for (grid, r, c) in grids {
+            self.grids[grid].set_cell(value, origin, r, c);
+        }
+    }
+
+    pub fn set_number(&mut self, value: Option<u8>, row: usize, col: usize) {
+        self.set_cell(value, CellOrigin::Given, row, col);
+    }
+
+    /// Return the numbers that can be put in a cell according to every grid that contains it
+    pub fn get_possible(&self, row: usize, col: usize) -> NumberOptions<SIZE> {
+        self.grids_at(row, col)
+            .fold(NumberOptions::full(), |acc, (grid, r, c)| acc & self.grids[grid].get_possible(r, c))
+    }
+
+    /// Copy the numbers of the shared cells to the grids that don't have them yet, and remove the
+    /// candidates that any grid forbids from the others. Return false if the grids contradict
+    /// each other
+    pub fn sync_shared_cells(&mut self) -> bool {
+        for [row, col] in self.cells() {
+            let grids: Vec<_> = self.grids_at(row, col).collect();
+            if grids.len() < 2 { continue; }
+
+            let filled = grids.iter().find_map(|&(grid, r, c)| {
+                self.grids[grid].get_number(r, c).map(|num| (num, self.grids[grid].get_origin(r, c).unwrap()))
+            });
+
+            match filled {
+                Some((num, origin)) => {
+                    for &(grid, r, c) in &grids {
+                        match self.grids[grid].get_number(r, c) {
+                            Some(other) if other != num => return false,
+                            Some(_) => {}
+                            None => self.grids[grid].set_cell(Some(num), origin, r, c),
+                        }
+                    }
+                }
+                None => {
+                    let possible = self.get_possible(row, col);
+                    if possible.empty() {
+                        return false;
+                    }
+                    for &(grid, r, c) in &grids {
+                        self.grids[grid].restrict(r, c, possible);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Return the first error of any grid
+    pub fn find_error(&self) -> Option<MultiGridError> {
+        self.grids.iter()
+            .enumerate()
+            .find_map(|(grid, board)| board.find_error().map(|error| MultiGridError { grid, error }))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.grids.iter().all(SudokuBoard::is_full)
+    }
+
+    /// Read the numbers of every cell that belongs to a grid, in reading order. Whitespace is
+    /// ignored, so the layout can be drawn with spaces for the cells outside the grids, like
+    /// [MultiGrid::to_literal] does. Return None if the number of cells is wrong
+    pub fn from_literal(offsets: Vec<[usize; 2]>, literal: &str) -> Option<Self> {
+        let mut result = Self::new(offsets)?;
+        let cells = result.cells();
+        let values = literal.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(char_to_cell)
+            .collect::<Option<Vec<Option<u8>>>>()?;
+        if values.len() != cells.len() || values.iter().flatten().any(|&o| o as usize > SIZE) {
+            return None;
+        }
+
+        for ([row, col], value) in cells.into_iter().zip(values) {
+            result.set_number(value, row, col);
+        }
+        Some(result)
+    }
+
+    /// Write one line per row, with a character for each cell and spaces where there's no grid
+    pub fn to_literal(&self) -> String {
+        let mut result = String::new();
+        for row in 0..self.height() {
+            let line: String = (0..self.width())
+                .map(|col| match self.grids_at(row, col).next() {
+                    Some(_) => cell_to_char(self.get_number(row, col)),
+                    None => ' ',
+                })
+                .collect();
+            result += line.trim_end();
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_grid::{Layout, MultiGrid};
+
+    #[test]
+    fn layouts() {
+        let samurai = MultiGrid::<9, 3>::from_layout(Layout::Samurai);
+        assert_eq!((samurai.height(), samurai.width()), (21, 21));
+        assert_eq!(samurai.cells().len(), 5 * 81 - 4 * 9);
+        assert_eq!(samurai.grids_at(7, 7).collect::<Vec<_>>(), vec![(0, 7, 7), (2, 1, 1)]);
+        assert_eq!(samurai.grids_at(10, 1).count(), 0);
+
+        let butterfly = MultiGrid::<9, 3>::from_layout(Layout::Butterfly);
+        assert_eq!(butterfly.cells().len(), 144);
+        assert_eq!(butterfly.grids_at(5, 5).count(), 4);
+
+        assert!(MultiGrid::<9, 3>::new(vec![[0, 0], [4, 4]]).is_none());
+    }
+
+    #[test]
+    fn shared_cells() {
+        let mut twodoku = MultiGrid::<9, 3>::from_layout(Layout::Twodoku);
+        twodoku.set_number(Some(5), 8, 8);
+        assert_eq!(twodoku.grids()[1].get_number(2, 2), Some(5));
+        // A 4 outside the first grid rules out the shared cells in the same row of the second grid
+        twodoku.grids_mut()[1].set_number(Some(4), 1, 5);
+        assert!(!twodoku.get_possible(7, 6).has_number(4));
+        assert!(twodoku.grids()[0].get_possible(7, 6).has_number(4));
+        assert!(twodoku.sync_shared_cells());
+        assert!(!twodoku.grids()[0].get_possible(7, 6).has_number(4));
+
+        let json = serde_json::to_string(&twodoku).unwrap();
+        let parsed: MultiGrid<9, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_literal(), twodoku.to_literal());
+
+        twodoku.grids_mut()[0].set_number(Some(3), 8, 8);
+        assert!(!twodoku.sync_shared_cells());
+        assert!(serde_json::from_str::<MultiGrid<9, 3>>(&serde_json::to_string(&twodoku).unwrap()).is_err());
+    }
+
+    #[test]
+    fn multi_grid_literal() {
+        let mut twodoku = MultiGrid::<9, 3>::from_layout(Layout::Twodoku);
+        twodoku.set_number(Some(1), 0, 0);
+        twodoku.set_number(Some(9), 14, 14);
+
+        let literal = twodoku.to_literal();
+        assert_eq!(literal.lines().count(), 15);
+        assert_eq!(literal.lines().last().unwrap(), "      ........9");
+
+        let parsed = MultiGrid::<9, 3>::from_literal(Layout::Twodoku.offsets::<9, 3>(), &literal).unwrap();
+        assert_eq!(parsed.to_literal(), literal);
+        assert!(MultiGrid::<9, 3>::from_literal(Layout::Twodoku.offsets::<9, 3>(), "123").is_none());
+    }
+}
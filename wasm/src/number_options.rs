@@ -1,5 +1,6 @@
 use std::fmt::{Debug, Formatter};
 use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
 pub struct NumberOptions<const SIZE: usize> {
@@ -9,6 +10,44 @@ pub struct NumberOptions<const SIZE: usize> {
 impl<const SIZE: usize> NumberOptions<SIZE> {
     const U8SIZE: u8 = SIZE as u8;
 
+    /// Return options containing every number from 1 to SIZE
+    pub fn full() -> Self {
+        NumberOptions {
+            data: ((1_u32 << SIZE) - 1) as u16
+        }
+    }
+
+    /// Return options containing only the number
+    pub fn single(num: u8) -> Self {
+        let mut result = Self::default();
+        result.add_number(num);
+        result
+    }
+
+    /// Return options containing the odd numbers
+    pub fn odd() -> Self {
+        NumberOptions {
+            data: (0x5555_u32 & ((1_u32 << SIZE) - 1)) as u16
+        }
+    }
+
+    /// Return options containing the even numbers
+    pub fn even() -> Self {
+        NumberOptions {
+            data: (0xAAAA_u32 & ((1_u32 << SIZE) - 1)) as u16
+        }
+    }
+
+    /// Return options containing every number from min to max, both included. Bounds outside
+    /// 1..=SIZE are clamped
+    pub fn range(min: i32, max: i32) -> Self {
+        let mut result = Self::default();
+        for num in min.max(1)..=max.min(SIZE as i32) {
+            result.add_number(num as u8);
+        }
+        result
+    }
+
     pub fn has_number(&self, num: u8) -> bool {
         (self.data >> (num - 1)) & 1 == 1
     }
@@ -36,13 +75,11 @@ impl<const SIZE: usize> NumberOptions<SIZE> {
     }
 
     pub fn first(&self) -> Option<u8> {
-        for i in 1..=Self::U8SIZE {
-            if self.has_number(i) {
-                return Some(i);
-            }
-        }
+        (1..=Self::U8SIZE).find(|&i| self.has_number(i))
+    }
 
-        None
+    pub fn last(&self) -> Option<u8> {
+        (1..=Self::U8SIZE).rev().find(|&i| self.has_number(i))
     }
 
     pub fn as_vec(&self) -> Vec<u8> {
@@ -125,6 +162,26 @@ impl<const SIZE: usize> Debug for NumberOptions<SIZE> {
     }
 }
 
+/// Serialized as the list of numbers it contains, like [1, 5, 9]
+impl<const SIZE: usize> Serialize for NumberOptions<SIZE> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_vec().serialize(serializer)
+    }
+}
+
+impl<'de, const SIZE: usize> Deserialize<'de> for NumberOptions<SIZE> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut result = NumberOptions::default();
+        for num in Vec::<u8>::deserialize(deserializer)? {
+            if num == 0 || num as usize > SIZE {
+                return Err(serde::de::Error::custom(format!("Invalid number {}", num)));
+            }
+            result.add_number(num);
+        }
+        Ok(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::number_options::NumberOptions;
@@ -142,4 +199,20 @@ mod tests {
         options.remove_number(2);
         println!("{:?}", options);
     }
+
+    #[test]
+    fn range() {
+        let options = NumberOptions::<9>::range(-1, 3);
+        assert_eq!(options.as_vec(), vec![1, 2, 3]);
+        assert_eq!(options.last(), Some(3));
+        assert!(NumberOptions::<9>::range(5, 4).empty());
+        assert_eq!(NumberOptions::<9>::range(8, 12), NumberOptions::single(8) | NumberOptions::single(9));
+    }
+
+    #[test]
+    fn parity() {
+        assert_eq!(NumberOptions::<9>::odd().as_vec(), vec![1, 3, 5, 7, 9]);
+        assert_eq!(NumberOptions::<9>::even().as_vec(), vec![2, 4, 6, 8]);
+        assert_eq!(NumberOptions::<16>::even().count(), 8);
+    }
 }
\ No newline at end of file
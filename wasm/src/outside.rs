@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use crate::constraint::Constraint;
+use crate::killer::sum_combinations;
+use crate::number_options::NumberOptions;
+use crate::solve_report::Message;
+
+/// Direction a Little Killer clue points to
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    DownRight,
+    DownLeft,
+    UpRight,
+    UpLeft,
+}
+
+impl Direction {
+    /// Row and column offsets of a step in the direction
+    pub fn offsets(&self) -> [isize; 2] {
+        match self {
+            Direction::DownRight => [1, 1],
+            Direction::DownLeft => [1, -1],
+            Direction::UpRight => [-1, 1],
+            Direction::UpLeft => [-1, -1],
+        }
+    }
+}
+
+/// Clue written outside the board, about the numbers in a row, column or diagonal. Rows and
+/// columns are 0-based. Serialized as an object whose "type" field names the variant in camelCase,
+/// like {"type": "rowSandwich", "row": 0, "sum": 12}
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OutsideClue {
+    /// Sum of the numbers between 1 and SIZE in the row
+    RowSandwich { row: usize, sum: u16 },
+    /// Sum of the numbers between 1 and SIZE in the column
+    ColSandwich { col: usize, sum: u16 },
+    /// Sum of the numbers along the diagonal that starts at row/col, next to the clue, and goes in
+    /// the direction. Numbers can repeat, unless a row, column or block forbids it
+    LittleKiller { row: usize, col: usize, direction: Direction, sum: u16 },
+}
+
+impl OutsideClue {
+    pub fn sum(&self) -> u16 {
+        match *self {
+            OutsideClue::RowSandwich { sum, .. }
+            | OutsideClue::ColSandwich { sum, .. }
+            | OutsideClue::LittleKiller { sum, .. } => sum,
+        }
+    }
+
+    /// Return the cells the clue is about, in order from the clue. Little Killer diagonals starting
+    /// outside the board are empty
+    pub fn cells_of<const SIZE: usize>(&self) -> Vec<[usize; 2]> {
+        match *self {
+            OutsideClue::RowSandwich { row, .. } => (0..SIZE).map(|col| [row, col]).collect(),
+            OutsideClue::ColSandwich { col, .. } => (0..SIZE).map(|row| [row, col]).collect(),
+            OutsideClue::LittleKiller { row, col, direction, .. } => {
+                let [row_offset, col_offset] = direction.offsets();
+                let mut result = Vec::new();
+                let mut cell = Some([row, col]);
+                while let Some([r, c]) = cell.filter(|&[r, c]| r < SIZE && c < SIZE) {
+                    result.push([r, c]);
+                    cell = r.checked_add_signed(row_offset).zip(c.checked_add_signed(col_offset)).map(|(r, c)| [r, c]);
+                }
+                result
+            }
+        }
+    }
+
+    fn is_sandwich_broken<const SIZE: usize>(&self, numbers: &[Option<u8>]) -> bool {
+        let first = numbers.iter().position(|&o| o == Some(1));
+        let last = numbers.iter().position(|&o| o == Some(SIZE as u8));
+        let (Some(first), Some(last)) = (first, last) else { return false; };
+
+        let between = &numbers[first.min(last) + 1..first.max(last)];
+        let filled_sum: u16 = between.iter().flatten().map(|&o| o as u16).sum();
+        let full = between.iter().all(Option::is_some);
+        filled_sum > self.sum() || (full && filled_sum != self.sum())
+    }
+
+    fn is_diagonal_broken<const SIZE: usize>(&self, numbers: &[Option<u8>]) -> bool {
+        let filled_sum: usize = numbers.iter().flatten().map(|&o| o as usize).sum();
+        let empty = numbers.iter().filter(|o| o.is_none()).count();
+        let sum = self.sum() as usize;
+        filled_sum + empty > sum || filled_sum + empty * SIZE < sum
+    }
+
+    /// Try every pair of cells for the 1 and the SIZE, and keep the candidates of the placements
+    /// whose numbers in between can add up to the sum
+    fn restrict_sandwich<const SIZE: usize>(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        let big = SIZE as u8;
+        let crust = NumberOptions::single(1) | NumberOptions::single(big);
+        let mut result = vec![NumberOptions::default(); options.len()];
+
+        for i in 0..options.len() {
+            for j in i + 1..options.len() {
+                for (a, b) in [(1, big), (big, 1)] {
+                    if !options[i].has_number(a) || !options[j].has_number(b) { continue; }
+
+                    let between = &options[i + 1..j];
+                    let mut candidates = NumberOptions::default();
+                    for &option in between {
+                        candidates |= option;
+                    }
+                    let candidates = candidates & !crust;
+
+                    let combinations: Vec<NumberOptions<SIZE>> = if between.is_empty() {
+                        if self.sum() == 0 { vec![NumberOptions::default()] } else { Vec::new() }
+                    } else {
+                        sum_combinations(self.sum(), between.len(), candidates)
+                            .into_iter()
+                            .filter(|&combination| between.iter().all(|&o| !(o & combination).empty()))
+                            .collect()
+                    };
+                    if combinations.is_empty() { continue; }
+
+                    let mut allowed = NumberOptions::default();
+                    for combination in combinations {
+                        allowed |= combination;
+                    }
+
+                    result[i].add_number(a);
+                    result[j].add_number(b);
+                    for (index, &option) in options.iter().enumerate() {
+                        if index > i && index < j {
+                            result[index] |= option & allowed;
+                        } else if index != i && index != j {
+                            result[index] |= option & !crust;
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn restrict_diagonal<const SIZE: usize>(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        if options.iter().any(NumberOptions::empty) {
+            return options.to_vec();
+        }
+
+        let sum = self.sum() as i32;
+        let min_total: i32 = options.iter().map(|o| o.first().unwrap() as i32).sum();
+        let max_total: i32 = options.iter().map(|o| o.last().unwrap() as i32).sum();
+
+        // Every cell gets what's left of the sum after the others take the most/least they can
+        options.iter()
+            .map(|option| {
+                let min = option.first().unwrap() as i32;
+                let max = option.last().unwrap() as i32;
+                *option & NumberOptions::range(sum - (max_total - max), sum - (min_total - min))
+            })
+            .collect()
+    }
+}
+
+impl<const SIZE: usize> Constraint<SIZE> for OutsideClue {
+    fn cells(&self) -> Cow<'_, [[usize; 2]]> {
+        Cow::Owned(self.cells_of::<SIZE>())
+    }
+
+    fn is_broken(&self, numbers: &[Option<u8>]) -> bool {
+        match self {
+            OutsideClue::RowSandwich { .. } | OutsideClue::ColSandwich { .. } => self.is_sandwich_broken::<SIZE>(numbers),
+            OutsideClue::LittleKiller { .. } => self.is_diagonal_broken::<SIZE>(numbers),
+        }
+    }
+
+    fn restrict(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        match self {
+            OutsideClue::RowSandwich { .. } | OutsideClue::ColSandwich { .. } => self.restrict_sandwich(options),
+            OutsideClue::LittleKiller { .. } => self.restrict_diagonal(options),
+        }
+    }
+
+    fn message(&self) -> Message {
+        match *self {
+            OutsideClue::RowSandwich { row, sum } => Message::RowSandwich { sum, row: row + 1 },
+            OutsideClue::ColSandwich { col, sum } => Message::ColSandwich { sum, col: col + 1 },
+            OutsideClue::LittleKiller { row, col, sum, .. } => Message::LittleKiller { sum, row: row + 1, col: col + 1 },
+        }
+    }
+
+    fn highlight_line(&self) -> [Option<usize>; 2] {
+        match *self {
+            OutsideClue::RowSandwich { row, .. } => [Some(row), None],
+            OutsideClue::ColSandwich { col, .. } => [None, Some(col)],
+            OutsideClue::LittleKiller { .. } => [None, None],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraint::Constraint;
+    use crate::number_options::NumberOptions;
+    use crate::outside::{Direction, OutsideClue};
+
+    #[test]
+    fn little_killer() {
+        let clue = OutsideClue::LittleKiller { row: 0, col: 2, direction: Direction::DownLeft, sum: 6 };
+        assert_eq!(clue.cells_of::<9>(), vec![[0, 2], [1, 1], [2, 0]]);
+        assert!(!Constraint::<9>::is_broken(&clue, &[Some(1), None, None]));
+        assert!(Constraint::<9>::is_broken(&clue, &[Some(5), None, None]));
+        assert!(Constraint::<9>::is_broken(&clue, &[Some(1), Some(2), Some(2)]));
+
+        let full = NumberOptions::<9>::full();
+        let result = clue.restrict(&[full, full, NumberOptions::single(3)]);
+        assert_eq!(result[0].as_vec(), vec![1, 2]);
+        assert_eq!(result[2].as_vec(), vec![3]);
+    }
+
+    #[test]
+    fn sandwich() {
+        let clue = OutsideClue::RowSandwich { row: 0, sum: 0 };
+        assert_eq!(clue.cells_of::<4>()[3], [0, 3]);
+        assert!(Constraint::<4>::is_broken(&clue, &[Some(1), Some(2), Some(4), None]));
+        assert!(!Constraint::<4>::is_broken(&clue, &[Some(2), Some(1), Some(4), None]));
+
+        // The 1 and the 4 must be next to each other, so with 4 at the end, 1 is right before it
+        let others = NumberOptions::<4>::range(1, 3);
+        let result = clue.restrict(&[others, others, others, NumberOptions::single(4)]);
+        assert_eq!(result[2].as_vec(), vec![1]);
+        assert_eq!(result[0].as_vec(), vec![2, 3]);
+
+        // Only 2 to 8 add up to 35, so the 1 and the 9 are at the ends
+        let clue = OutsideClue::ColSandwich { col: 0, sum: 35 };
+        let result = clue.restrict(&[NumberOptions::<9>::full(); 9]);
+        assert_eq!(result[0].as_vec(), vec![1, 9]);
+        assert_eq!(result[4].as_vec(), vec![2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use crate::constraint::Constraint;
+use crate::number_options::NumberOptions;
+use crate::solve_report::Message;
+use crate::util::{cell_from_literal, cell_to_literal};
+
+/// Relation between the numbers of two adjacent cells
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PairKind {
+    /// Kropki white dot, the numbers are consecutive
+    White,
+    /// Kropki black dot, one number is double the other
+    Black,
+    /// The numbers add up to 10
+    X,
+    /// The numbers add up to 5
+    V,
+    /// The first number is greater than the second
+    GreaterThan,
+}
+
+impl PairKind {
+    /// Whether the numbers follow the relation
+    pub fn holds(&self, a: u8, b: u8) -> bool {
+        match self {
+            PairKind::White => a.abs_diff(b) == 1,
+            PairKind::Black => a == 2 * b || b == 2 * a,
+            PairKind::X => a + b == 10,
+            PairKind::V => a + b == 5,
+            PairKind::GreaterThan => a > b,
+        }
+    }
+
+    /// Whether the kind is a Kropki dot, otherwise a "negative" rule about dots doesn't apply
+    pub fn is_kropki(&self) -> bool {
+        matches!(self, PairKind::White | PairKind::Black)
+    }
+
+    pub fn is_xv(&self) -> bool {
+        matches!(self, PairKind::X | PairKind::V)
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            PairKind::White => "w",
+            PairKind::Black => "b",
+            PairKind::X => "x",
+            PairKind::V => "v",
+            PairKind::GreaterThan => ">",
+        }
+    }
+}
+
+/// Clue between two orthogonally adjacent cells, as [row, col], 0-based.
+/// Serialized as {"kind", "cells"}
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PairClue {
+    pub kind: PairKind,
+    pub cells: [[usize; 2]; 2],
+}
+
+impl PairClue {
+    pub fn new(kind: PairKind, first: [usize; 2], second: [usize; 2]) -> Self {
+        PairClue { kind, cells: [first, second] }
+    }
+
+    /// Read a clue in the format "w: r1c1 r1c2", where the kind is "w" for a white dot, "b" for a
+    /// black dot, "x", "v" or ">" for the first cell being greater than the second
+    pub fn from_literal(literal: &str) -> Option<Self> {
+        let (kind, cells) = literal.split_once(':')?;
+        let kind = match kind.trim().to_ascii_lowercase().as_str() {
+            "w" => PairKind::White,
+            "b" => PairKind::Black,
+            "x" => PairKind::X,
+            "v" => PairKind::V,
+            ">" => PairKind::GreaterThan,
+            _ => return None,
+        };
+
+        let cells = cells.split_whitespace()
+            .map(cell_from_literal)
+            .collect::<Option<Vec<[usize; 2]>>>()?;
+        match cells[..] {
+            [first, second] => Some(PairClue::new(kind, first, second)),
+            _ => None,
+        }
+    }
+
+    /// Write the clue in the format "w: r1c1 r1c2"
+    pub fn to_literal(&self) -> String {
+        format!("{}: {} {}", self.kind.symbol(), cell_to_literal(self.cells[0]), cell_to_literal(self.cells[1]))
+    }
+
+    /// Read a list of clues, one per line. Return None if any line is invalid
+    pub fn list_from_literal(literal: &str) -> Option<Vec<Self>> {
+        literal.lines()
+            .filter(|o| !o.trim().is_empty())
+            .map(PairClue::from_literal)
+            .collect()
+    }
+
+    /// Whether the clue is between the two cells, in any order
+    pub fn is_between(&self, a: [usize; 2], b: [usize; 2]) -> bool {
+        self.cells == [a, b] || self.cells == [b, a]
+    }
+}
+
+/// Return the numbers among the options that follow the relation with at least one of the others
+fn supported<const SIZE: usize>(options: NumberOptions<SIZE>, others: NumberOptions<SIZE>, holds: impl Fn(u8, u8) -> bool) -> NumberOptions<SIZE> {
+    let mut result = NumberOptions::default();
+    for a in options.as_vec() {
+        if others.as_vec().into_iter().any(|b| holds(a, b)) {
+            result.add_number(a);
+        }
+    }
+    result
+}
+
+impl<const SIZE: usize> Constraint<SIZE> for PairClue {
+    fn cells(&self) -> Cow<'_, [[usize; 2]]> {
+        Cow::Borrowed(&self.cells)
+    }
+
+    fn is_broken(&self, numbers: &[Option<u8>]) -> bool {
+        match (numbers[0], numbers[1]) {
+            (Some(a), Some(b)) => !self.kind.holds(a, b),
+            _ => false,
+        }
+    }
+
+    fn restrict(&self, options: &[NumberOptions<SIZE>]) -> Vec<NumberOptions<SIZE>> {
+        vec![
+            supported(options[0], options[1], |a, b| self.kind.holds(a, b)),
+            supported(options[1], options[0], |b, a| self.kind.holds(a, b)),
+        ]
+    }
+
+    fn message(&self) -> Message {
+        let [[row, col], [row2, col2]] = self.cells;
+        Message::PairClue { kind: self.kind, row: row + 1, col: col + 1, row2: row2 + 1, col2: col2 + 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraint::Constraint;
+    use crate::number_options::NumberOptions;
+    use crate::pairs::{PairClue, PairKind};
+
+    #[test]
+    fn pair_literal() {
+        let clue = PairClue::from_literal("> : r1c1 r1c2").unwrap();
+        assert_eq!(clue, PairClue::new(PairKind::GreaterThan, [0, 0], [0, 1]));
+        assert_eq!(clue.to_literal(), ">: r1c1 r1c2");
+        assert!(clue.is_between([0, 1], [0, 0]));
+
+        assert_eq!(PairClue::list_from_literal("w: r1c1 r2c1\nB: r5c5 r5c6\n").unwrap().len(), 2);
+        assert!(PairClue::from_literal("x: r1c1").is_none());
+        assert!(PairClue::from_literal("o: r1c1 r1c2").is_none());
+    }
+
+    #[test]
+    fn pair_restrict() {
+        let full = NumberOptions::<9>::full();
+        let black = PairClue::new(PairKind::Black, [0, 0], [0, 1]);
+        let result = black.restrict(&[NumberOptions::single(3), full]);
+        assert_eq!(result[1].as_vec(), vec![6]);
+        assert_eq!(black.restrict(&[full, full])[0].as_vec(), vec![1, 2, 3, 4, 6, 8]);
+
+        let greater = PairClue::new(PairKind::GreaterThan, [0, 0], [0, 1]);
+        let result = greater.restrict(&[full, full]);
+        assert!(!result[0].has_number(1) && !result[1].has_number(9));
+
+        let v = PairClue::new(PairKind::V, [0, 0], [1, 0]);
+        assert_eq!(v.restrict(&[full, full])[0].as_vec(), vec![1, 2, 3, 4]);
+        assert!(Constraint::<9>::is_broken(&v, &[Some(2), Some(2)]));
+        assert!(!Constraint::<9>::is_broken(&v, &[Some(2), None]));
+    }
+}
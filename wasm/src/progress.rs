@@ -0,0 +1,101 @@
+use crate::sudoku_board::SudokuBoard;
+use crate::sudoku_solver::SudokuSolver;
+
+/// Result of comparing a board being filled by the player with the solution of its givens
+pub struct ProgressReport<const SIZE: usize, const BLOCK_SIZE: usize> {
+    /// Cells filled by the player whose value disagrees with the solution, as [row, col]
+    pub wrong_cells: Vec<[usize; 2]>,
+    /// Whether the current board can still be completed without breaking any rule
+    pub solvable: bool,
+    /// Whether the givens have a single solution. Otherwise the player's cells aren't compared
+    /// with it, since a correct entry could disagree with the solution that was picked
+    pub unique: bool,
+    pub solution: SudokuBoard<SIZE, BLOCK_SIZE>,
+}
+
+/// Solve the original givens and list every cell entered by the player that disagrees with
+/// that solution. This catches digits that break no rule but make the puzzle unsolvable.
+/// Puzzles with more than one solution only check whether the board is solvable.
+/// Return None if the givens themselves can't be solved.
+pub fn check_progress<const SIZE: usize, const BLOCK_SIZE: usize>(original: &SudokuBoard<SIZE, BLOCK_SIZE>,
+                                                                  current: &SudokuBoard<SIZE, BLOCK_SIZE>)
+                                                                  -> Option<ProgressReport<SIZE, BLOCK_SIZE>> {
+    let mut solver = SudokuSolver::new(0);
+    let solution = solver.solve(original)?;
+    let unique = solver.count_solutions(original, 2) == 1;
+
+    let mut wrong_cells = Vec::new();
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            // Only the cells filled by the player are checked. The frontend writes them without a
+            // suffix, so they are found by comparing with the original givens
+            if !unique || original.get_number(row, col).is_some() { continue; }
+
+            if let Some(value) = current.get_number(row, col) {
+                if solution.get_number(row, col) != Some(value) {
+                    wrong_cells.push([row, col]);
+                }
+            }
+        }
+    }
+
+    // A board that already breaks a rule can't be completed. The solver doesn't check that by
+    // itself, so the board is validated first
+    let solvable = (unique && wrong_cells.is_empty())
+        || (SudokuBoard::<SIZE, BLOCK_SIZE>::from_literal_checked(&current.to_literal()).is_ok()
+        && solver.solve(current).is_some());
+
+    Some(ProgressReport {
+        wrong_cells,
+        solvable,
+        unique,
+        solution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::check_progress;
+    use crate::sudoku_board::{DefaultBoard, SudokuBoard};
+    use crate::sudoku_examples::EASY_LITERALS;
+
+    #[test]
+    fn correct_progress() {
+        let original = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        let mut current = original.clone();
+        // The solution of the first easy example has 8 in the top left corner
+        current.set_user_number(Some(8), 0, 0);
+
+        let report = check_progress(&original, &current).unwrap();
+        assert!(report.wrong_cells.is_empty());
+        assert!(report.solvable);
+    }
+
+    #[test]
+    fn wrong_but_legal_entry() {
+        let original = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        let mut current = original.clone();
+        // 5 breaks no rule in the top left corner, but the solution has 8 there
+        current.set_user_number(Some(5), 0, 0);
+
+        let report = check_progress(&original, &current).unwrap();
+        assert_eq!(report.wrong_cells, vec![[0, 0]]);
+        assert!(!report.solvable);
+    }
+
+    #[test]
+    fn several_solutions() {
+        // Only the first row is given, so the entry in the second row is correct for some solutions
+        let original = SudokuBoard::<4, 2>::from_literal("1 2 3 4 _ _ _ _ _ _ _ _ _ _ _ _");
+        let mut current = original.clone();
+        current.set_user_number(Some(3), 1, 0);
+
+        let report = check_progress(&original, &current).unwrap();
+        assert!(!report.unique);
+        assert!(report.wrong_cells.is_empty());
+        assert!(report.solvable);
+
+        current.set_user_number(Some(1), 1, 1);
+        assert!(!check_progress(&original, &current).unwrap().solvable);
+    }
+}
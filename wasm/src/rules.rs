@@ -0,0 +1,603 @@
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::constraint::Constraint;
+use crate::killer::Cage;
+use crate::lines::Line;
+use crate::number_options::NumberOptions;
+use crate::outside::OutsideClue;
+use crate::pairs::{PairClue, PairKind};
+use crate::util::Array2D;
+
+/// House of a variant puzzle that must contain every number once, like rows and columns.
+/// Serialized as {"type", "value"}, like {"type": "window", "value": [0, 1]}
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum ExtraHouse {
+    /// 0 is the main diagonal, from the top left corner, and 1 is the anti-diagonal (Sudoku-X)
+    Diagonal(usize),
+    /// Window of a Windoku/Hyper Sudoku by row and column. Windows have the size of a block, and
+    /// are separated from the border and from each other by one cell
+    Window(usize, usize),
+}
+
+impl ExtraHouse {
+    /// Return the cells of the house, as [row, col]
+    pub fn cells<const SIZE: usize, const BLOCK_SIZE: usize>(&self) -> [[usize; 2]; SIZE] {
+        let mut result = [[0, 0]; SIZE];
+        for (index, cell) in result.iter_mut().enumerate() {
+            *cell = match *self {
+                ExtraHouse::Diagonal(0) => [index, index],
+                ExtraHouse::Diagonal(_) => [index, SIZE - 1 - index],
+                ExtraHouse::Window(row, col) => [
+                    1 + row * (BLOCK_SIZE + 1) + index / BLOCK_SIZE,
+                    1 + col * (BLOCK_SIZE + 1) + index % BLOCK_SIZE,
+                ],
+            };
+        }
+        result
+    }
+}
+
+/// Offsets of the cells a chess knight's move apart
+const KNIGHT_MOVES: [[isize; 2]; 8] = [[-2, -1], [-2, 1], [-1, -2], [-1, 2], [1, -2], [1, 2], [2, -1], [2, 1]];
+/// Offsets of the cells a chess king's move apart
+const KING_MOVES: [[isize; 2]; 8] = [[-1, -1], [-1, 0], [-1, 1], [0, -1], [0, 1], [1, -1], [1, 0], [1, 1]];
+
+/// Return the cells inside the board at each offset from a cell
+fn cells_at<const SIZE: usize>(row: usize, col: usize, offsets: &'static [[isize; 2]]) -> impl Iterator<Item=[usize; 2]> {
+    offsets.iter().filter_map(move |&[row_offset, col_offset]| {
+        let r = row.checked_add_signed(row_offset)?;
+        let c = col.checked_add_signed(col_offset)?;
+        (r < SIZE && c < SIZE).then_some([r, c])
+    })
+}
+
+/// Extra rules of variant puzzles and the shape of the regions. They never change while solving,
+/// so every copy of a board shares the same instance
+#[derive(Clone, Debug)]
+pub struct Rules<const SIZE: usize, const BLOCK_SIZE: usize> {
+    /// Region that contains each cell. Regions are the rectangular blocks unless it's a Jigsaw Sudoku
+    region_of: Array2D<usize, SIZE>,
+    /// Cells of each region, as [row, col]
+    region_cells: Array2D<[usize; 2], SIZE>,
+    jigsaw: bool,
+    cages: Vec<Cage>,
+    /// Index of the cage that contains each cell
+    cage_of: Array2D<Option<usize>, SIZE>,
+    extra_houses: Vec<ExtraHouse>,
+    /// Bits with the indices of the extra houses that contain each cell
+    extra_houses_of: Array2D<u32, SIZE>,
+    /// Cells a chess knight's move apart can't contain the same number
+    anti_knight: bool,
+    /// Cells a chess king's move apart can't contain the same number
+    anti_king: bool,
+    lines: Vec<Line>,
+    pairs: Vec<PairClue>,
+    /// Every Kropki dot is given, so adjacent cells without one can't be consecutive or double
+    negative_kropki: bool,
+    /// Every X and V is given, so adjacent cells without one can't add up to 10 or 5
+    negative_xv: bool,
+    outside: Vec<OutsideClue>,
+    /// Numbers each cell can contain, like only odd or only even numbers
+    masks: Array2D<NumberOptions<SIZE>, SIZE>,
+    has_masks: bool,
+    /// Rules defined outside the crate
+    custom: Vec<Arc<dyn Constraint<SIZE>>>,
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> Rules<SIZE, BLOCK_SIZE> {
+    pub fn new() -> Self {
+        let mut region_of = [[0; SIZE]; SIZE];
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                region_of[row][col] = (row / BLOCK_SIZE) * BLOCK_SIZE + col / BLOCK_SIZE;
+            }
+        }
+
+        Rules {
+            region_of,
+            region_cells: Self::cells_by_region(&region_of),
+            jigsaw: false,
+            cages: Vec::new(),
+            cage_of: [[None; SIZE]; SIZE],
+            extra_houses: Vec::new(),
+            extra_houses_of: [[0; SIZE]; SIZE],
+            anti_knight: false,
+            anti_king: false,
+            lines: Vec::new(),
+            pairs: Vec::new(),
+            negative_kropki: false,
+            negative_xv: false,
+            outside: Vec::new(),
+            masks: [[NumberOptions::full(); SIZE]; SIZE],
+            has_masks: false,
+            custom: Vec::new(),
+        }
+    }
+
+    fn cells_by_region(region_of: &Array2D<usize, SIZE>) -> Array2D<[usize; 2], SIZE> {
+        let mut result = [[[0, 0]; SIZE]; SIZE];
+        let mut counts = [0; SIZE];
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let region = region_of[row][col];
+                result[region][counts[region]] = [row, col];
+                counts[region] += 1;
+            }
+        }
+        result
+    }
+
+    /// Whether there's no rule besides the rows, columns and regions
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cages.is_empty() && self.extra_houses.is_empty() && !self.anti_knight && !self.anti_king
+            && self.lines.is_empty() && self.pairs.is_empty() && !self.negative_kropki && !self.negative_xv
+            && self.outside.is_empty() && !self.has_masks && self.custom.is_empty()
+    }
+
+    #[inline]
+    pub fn region_of(&self, row: usize, col: usize) -> usize {
+        self.region_of[row][col]
+    }
+
+    pub fn region_cells(&self, region: usize) -> &[[usize; 2]; SIZE] {
+        &self.region_cells[region]
+    }
+
+    /// Whether the regions aren't the rectangular blocks
+    pub fn is_jigsaw(&self) -> bool {
+        self.jigsaw
+    }
+
+    /// Replace the blocks with irregular regions. Every region must have SIZE cells, and its id
+    /// must be less than SIZE. Return false if the map is invalid.
+    /// Should only be called through [crate::sudoku_board::SudokuBoard::set_regions], which also
+    /// updates the numbers used in each region
+    pub(crate) fn set_regions(&mut self, region_of: Array2D<usize, SIZE>) -> bool {
+        let mut counts = [0; SIZE];
+        for &region in region_of.iter().flatten() {
+            if region >= SIZE {
+                return false;
+            }
+            counts[region] += 1;
+        }
+        if counts.iter().any(|&o| o != SIZE) {
+            return false;
+        }
+
+        self.jigsaw = region_of != Self::new().region_of;
+        self.region_cells = Self::cells_by_region(&region_of);
+        self.region_of = region_of;
+        true
+    }
+
+    /// Read a region map where every character that isn't whitespace is the label of the
+    /// region of a cell, like "AAABBBCCC". Labels get ids in the order they first appear
+    pub fn regions_from_literal(literal: &str) -> Option<Array2D<usize, SIZE>> {
+        let mut labels = Vec::<char>::with_capacity(SIZE);
+        let mut result = [[0; SIZE]; SIZE];
+        let mut index = 0;
+
+        for c in literal.chars().filter(|o| !o.is_whitespace()) {
+            if index >= SIZE * SIZE {
+                return None;
+            }
+
+            let region = match labels.iter().position(|&o| o == c) {
+                Some(region) => region,
+                None => {
+                    labels.push(c);
+                    labels.len() - 1
+                }
+            };
+            result[index / SIZE][index % SIZE] = region;
+            index += 1;
+        }
+
+        if index != SIZE * SIZE {
+            return None;
+        }
+        Some(result)
+    }
+
+    /// Write the region map with a letter for each region, one row per line
+    pub fn regions_to_literal(&self) -> String {
+        let mut result = String::new();
+        for row in &self.region_of {
+            for &region in row {
+                result.push((b'A' + region as u8) as char);
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Add a cage. Return false if it's outside the board or overlaps another cage
+    pub fn add_cage(&mut self, cage: Cage) -> bool {
+        if cage.cells.iter().any(|&[row, col]| row >= SIZE || col >= SIZE || self.cage_of[row][col].is_some()) {
+            return false;
+        }
+
+        for &[row, col] in &cage.cells {
+            self.cage_of[row][col] = Some(self.cages.len());
+        }
+        self.cages.push(cage);
+        true
+    }
+
+    fn add_extra_house(&mut self, house: ExtraHouse) {
+        if self.extra_houses.contains(&house) {
+            return;
+        }
+
+        for [row, col] in house.cells::<SIZE, BLOCK_SIZE>() {
+            self.extra_houses_of[row][col] |= 1 << self.extra_houses.len();
+        }
+        self.extra_houses.push(house);
+    }
+
+    /// Add the two main diagonals as houses (Sudoku-X)
+    pub fn add_diagonals(&mut self) {
+        self.add_extra_house(ExtraHouse::Diagonal(0));
+        self.add_extra_house(ExtraHouse::Diagonal(1));
+    }
+
+    /// Add the windows between the blocks as houses (Windoku/Hyper Sudoku)
+    pub fn add_windows(&mut self) {
+        for row in 0..BLOCK_SIZE - 1 {
+            for col in 0..BLOCK_SIZE - 1 {
+                self.add_extra_house(ExtraHouse::Window(row, col));
+            }
+        }
+    }
+
+    pub fn has_diagonals(&self) -> bool {
+        self.extra_houses.contains(&ExtraHouse::Diagonal(0))
+    }
+
+    pub fn has_windows(&self) -> bool {
+        self.extra_houses.contains(&ExtraHouse::Window(0, 0))
+    }
+
+    pub fn extra_houses(&self) -> &[ExtraHouse] {
+        &self.extra_houses
+    }
+
+    /// Return the extra houses that contain a cell
+    pub fn extra_houses_of(&self, row: usize, col: usize) -> impl Iterator<Item=&ExtraHouse> {
+        let bits = self.extra_houses_of[row][col];
+        self.extra_houses.iter()
+            .enumerate()
+            .filter(move |(index, _)| (bits >> index) & 1 == 1)
+            .map(|(_, house)| house)
+    }
+
+    pub fn set_anti_knight(&mut self, anti_knight: bool) {
+        self.anti_knight = anti_knight;
+    }
+
+    pub fn is_anti_knight(&self) -> bool {
+        self.anti_knight
+    }
+
+    pub fn set_anti_king(&mut self, anti_king: bool) {
+        self.anti_king = anti_king;
+    }
+
+    pub fn is_anti_king(&self) -> bool {
+        self.anti_king
+    }
+
+    /// Return the cells a knight's move apart from a cell, if the anti-knight rule is active
+    pub fn knight_cells(&self, row: usize, col: usize) -> impl Iterator<Item=[usize; 2]> {
+        let offsets: &'static [[isize; 2]] = if self.anti_knight { &KNIGHT_MOVES } else { &[] };
+        cells_at::<SIZE>(row, col, offsets)
+    }
+
+    /// Return the cells a king's move apart from a cell, if the anti-king rule is active
+    pub fn king_cells(&self, row: usize, col: usize) -> impl Iterator<Item=[usize; 2]> {
+        let offsets: &'static [[isize; 2]] = if self.anti_king { &KING_MOVES } else { &[] };
+        cells_at::<SIZE>(row, col, offsets)
+    }
+
+    /// Add a thermometer, arrow or palindrome. Return false if it's outside the board, goes
+    /// through a cell twice or is too short to mean anything
+    pub fn add_line(&mut self, line: Line) -> bool {
+        if line.cells.len() < 2 || line.cells.iter().any(|&[row, col]| row >= SIZE || col >= SIZE) {
+            return false;
+        }
+        if line.cells.iter().enumerate().any(|(index, cell)| line.cells[..index].contains(cell)) {
+            return false;
+        }
+
+        self.lines.push(line);
+        true
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Add a clue between two cells. Return false if they aren't orthogonally adjacent
+    pub fn add_pair(&mut self, pair: PairClue) -> bool {
+        let [[row, col], [row2, col2]] = pair.cells;
+        if row >= SIZE || col >= SIZE || row2 >= SIZE || col2 >= SIZE || row.abs_diff(row2) + col.abs_diff(col2) != 1 {
+            return false;
+        }
+
+        self.pairs.push(pair);
+        true
+    }
+
+    pub fn pairs(&self) -> &[PairClue] {
+        &self.pairs
+    }
+
+    pub fn set_negative_kropki(&mut self, negative_kropki: bool) {
+        self.negative_kropki = negative_kropki;
+    }
+
+    pub fn is_negative_kropki(&self) -> bool {
+        self.negative_kropki
+    }
+
+    pub fn set_negative_xv(&mut self, negative_xv: bool) {
+        self.negative_xv = negative_xv;
+    }
+
+    pub fn is_negative_xv(&self) -> bool {
+        self.negative_xv
+    }
+
+    /// Return the relations that the numbers of two adjacent cells can't follow, because the
+    /// clue would be given
+    pub fn forbidden_pair_kinds(&self, a: [usize; 2], b: [usize; 2]) -> Vec<PairKind> {
+        let mut result = Vec::new();
+        if !self.negative_kropki && !self.negative_xv {
+            return result;
+        }
+
+        let clues: Vec<PairKind> = self.pairs.iter().filter(|o| o.is_between(a, b)).map(|o| o.kind).collect();
+        if self.negative_kropki && !clues.iter().any(PairKind::is_kropki) {
+            result.extend([PairKind::White, PairKind::Black]);
+        }
+        if self.negative_xv && !clues.iter().any(PairKind::is_xv) {
+            result.extend([PairKind::X, PairKind::V]);
+        }
+        result
+    }
+
+    /// Allow only the numbers of the mask in a cell
+    pub fn set_mask(&mut self, row: usize, col: usize, mask: NumberOptions<SIZE>) {
+        self.masks[row][col] = mask;
+        self.has_masks = self.masks.iter().flatten().any(|o| !o.all());
+    }
+
+    /// Return the numbers a cell can contain according to its mask
+    #[inline]
+    pub fn mask(&self, row: usize, col: usize) -> NumberOptions<SIZE> {
+        self.masks[row][col]
+    }
+
+    /// Whether any cell has a mask that doesn't allow every number
+    pub fn has_masks(&self) -> bool {
+        self.has_masks
+    }
+
+    /// Read masks where each cell is "o" for odd, "e" for even or any other character for no
+    /// restriction, like "o..e.....". Whitespace is ignored
+    pub fn masks_from_literal(literal: &str) -> Option<Array2D<NumberOptions<SIZE>, SIZE>> {
+        let mut result = [[NumberOptions::full(); SIZE]; SIZE];
+        let mut index = 0;
+
+        for c in literal.chars().filter(|o| !o.is_whitespace()) {
+            if index >= SIZE * SIZE {
+                return None;
+            }
+
+            result[index / SIZE][index % SIZE] = match c {
+                'o' | 'O' => NumberOptions::odd(),
+                'e' | 'E' => NumberOptions::even(),
+                _ => NumberOptions::full(),
+            };
+            index += 1;
+        }
+
+        if index != SIZE * SIZE {
+            return None;
+        }
+        Some(result)
+    }
+
+    /// Add a clue outside the board. Return false if its row or column is outside the board, or
+    /// the cell before the start of a Little Killer diagonal isn't outside the board
+    pub fn add_outside(&mut self, clue: OutsideClue) -> bool {
+        let valid = match clue {
+            OutsideClue::RowSandwich { row, .. } => row < SIZE,
+            OutsideClue::ColSandwich { col, .. } => col < SIZE,
+            OutsideClue::LittleKiller { row, col, direction, .. } => {
+                let [row_offset, col_offset] = direction.offsets();
+                let before = row.checked_add_signed(-row_offset).zip(col.checked_add_signed(-col_offset));
+                row < SIZE && col < SIZE && before.is_none_or(|(r, c)| r >= SIZE || c >= SIZE)
+            }
+        };
+        if !valid {
+            return false;
+        }
+
+        self.outside.push(clue);
+        true
+    }
+
+    pub fn outside(&self) -> &[OutsideClue] {
+        &self.outside
+    }
+
+    /// Add a rule defined outside the crate. Return false if any of its cells is outside the board
+    /// or it has no cells
+    pub fn add_constraint(&mut self, constraint: Arc<dyn Constraint<SIZE>>) -> bool {
+        let cells = constraint.cells();
+        if cells.is_empty() || cells.iter().any(|&[row, col]| row >= SIZE || col >= SIZE) {
+            return false;
+        }
+
+        self.custom.push(constraint);
+        true
+    }
+
+    /// Rules added with [Rules::add_constraint]. They can't be serialized
+    pub fn custom_constraints(&self) -> &[Arc<dyn Constraint<SIZE>>] {
+        &self.custom
+    }
+
+    /// Return the rules about groups of cells that the solver handles through [Constraint]
+    pub(crate) fn constraints(&self) -> impl Iterator<Item=&dyn Constraint<SIZE>> {
+        self.lines.iter().map(|o| o as &dyn Constraint<SIZE>)
+            .chain(self.pairs.iter().map(|o| o as &dyn Constraint<SIZE>))
+            .chain(self.outside.iter().map(|o| o as &dyn Constraint<SIZE>))
+            .chain(self.custom.iter().map(|o| o.as_ref()))
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    pub fn cage_of(&self, row: usize, col: usize) -> Option<&Cage> {
+        self.cage_of[row][col].map(|o| &self.cages[o])
+    }
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> Default for Rules<SIZE, BLOCK_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lines::Line;
+    use crate::number_options::NumberOptions;
+    use crate::outside::{Direction, OutsideClue};
+    use crate::pairs::{PairClue, PairKind};
+    use crate::rules::{ExtraHouse, Rules};
+
+    #[test]
+    fn default_regions() {
+        let rules = Rules::<9, 3>::new();
+        assert!(!rules.is_jigsaw());
+        assert_eq!(rules.region_of(4, 5), 4);
+        assert_eq!(rules.region_cells(8)[0], [6, 6]);
+    }
+
+    #[test]
+    fn thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Rules<9, 3>>();
+    }
+
+    #[test]
+    fn extra_houses() {
+        let mut rules = Rules::<9, 3>::new();
+        rules.add_diagonals();
+        rules.add_windows();
+        assert_eq!(rules.extra_houses().len(), 6);
+
+        assert_eq!(ExtraHouse::Diagonal(1).cells::<9, 3>()[0], [0, 8]);
+        assert_eq!(ExtraHouse::Window(1, 0).cells::<9, 3>()[0], [5, 1]);
+        assert_eq!(ExtraHouse::Window(1, 1).cells::<9, 3>()[8], [7, 7]);
+
+        // The center is on both diagonals, and the cell below it is on none
+        assert_eq!(rules.extra_houses_of(4, 4).count(), 2);
+        assert_eq!(rules.extra_houses_of(5, 4).count(), 0);
+        assert_eq!(rules.extra_houses_of(1, 1).collect::<Vec<_>>(), vec![&ExtraHouse::Diagonal(0), &ExtraHouse::Window(0, 0)]);
+    }
+
+    #[test]
+    fn chess_moves() {
+        let mut rules = Rules::<9, 3>::new();
+        assert_eq!(rules.knight_cells(4, 4).count(), 0);
+
+        rules.set_anti_knight(true);
+        rules.set_anti_king(true);
+        assert!(!rules.is_empty());
+        assert_eq!(rules.knight_cells(4, 4).count(), 8);
+        assert_eq!(rules.knight_cells(0, 0).collect::<Vec<_>>(), vec![[1, 2], [2, 1]]);
+        assert_eq!(rules.king_cells(8, 8).collect::<Vec<_>>(), vec![[7, 7], [7, 8], [8, 7]]);
+    }
+
+    #[test]
+    fn lines() {
+        let mut rules = Rules::<9, 3>::new();
+        assert!(rules.add_line(Line::thermometer(vec![[0, 0], [0, 1], [1, 1]])));
+        assert!(!rules.add_line(Line::arrow(vec![[0, 0]])));
+        assert!(!rules.add_line(Line::palindrome(vec![[0, 0], [0, 9]])));
+        assert!(!rules.add_line(Line::palindrome(vec![[0, 0], [0, 1], [0, 0]])));
+        assert_eq!(rules.constraints().count(), 1);
+        assert!(!rules.is_empty());
+    }
+
+    #[test]
+    fn pairs() {
+        let mut rules = Rules::<9, 3>::new();
+        assert!(rules.add_pair(PairClue::new(PairKind::White, [0, 0], [0, 1])));
+        assert!(!rules.add_pair(PairClue::new(PairKind::X, [0, 0], [1, 1])));
+        assert!(rules.forbidden_pair_kinds([0, 0], [0, 1]).is_empty());
+
+        rules.set_negative_kropki(true);
+        rules.set_negative_xv(true);
+        assert_eq!(rules.forbidden_pair_kinds([0, 1], [0, 0]), vec![PairKind::X, PairKind::V]);
+        assert_eq!(rules.forbidden_pair_kinds([1, 0], [0, 0]).len(), 4);
+    }
+
+    #[test]
+    fn outside_clues() {
+        let mut rules = Rules::<9, 3>::new();
+        assert!(rules.add_outside(OutsideClue::RowSandwich { row: 8, sum: 10 }));
+        assert!(!rules.add_outside(OutsideClue::ColSandwich { col: 9, sum: 10 }));
+        assert!(rules.add_outside(OutsideClue::LittleKiller { row: 0, col: 3, direction: Direction::DownRight, sum: 20 }));
+        assert!(rules.add_outside(OutsideClue::LittleKiller { row: 8, col: 8, direction: Direction::UpLeft, sum: 45 }));
+        // The clue would be inside the board, at r1c3
+        assert!(!rules.add_outside(OutsideClue::LittleKiller { row: 1, col: 3, direction: Direction::DownRight, sum: 20 }));
+        assert_eq!(rules.constraints().count(), 3);
+    }
+
+    #[test]
+    fn masks() {
+        let mut rules = Rules::<4, 2>::new();
+        assert!(!rules.has_masks());
+
+        let masks = Rules::<4, 2>::masks_from_literal("o.e. .... .... ...o").unwrap();
+        for row in 0..4 {
+            for col in 0..4 {
+                rules.set_mask(row, col, masks[row][col]);
+            }
+        }
+        assert!(rules.has_masks() && !rules.is_empty());
+        assert_eq!(rules.mask(0, 2).as_vec(), vec![2, 4]);
+        assert_eq!(rules.mask(3, 3).as_vec(), vec![1, 3]);
+        assert!(rules.mask(1, 1).all());
+
+        rules.set_mask(0, 0, NumberOptions::full());
+        rules.set_mask(0, 2, NumberOptions::full());
+        rules.set_mask(3, 3, NumberOptions::full());
+        assert!(!rules.has_masks());
+        assert!(Rules::<4, 2>::masks_from_literal("o.e.").is_none());
+    }
+
+    #[test]
+    fn regions_literal() {
+        let literal = "AABB\nAABB\nCCDD\nCDDC\n";
+        let regions = Rules::<4, 2>::regions_from_literal(literal).unwrap();
+        assert_eq!(regions[3], [2, 3, 3, 2]);
+
+        let mut rules = Rules::<4, 2>::new();
+        assert!(rules.set_regions(regions));
+        assert!(rules.is_jigsaw());
+        assert_eq!(rules.regions_to_literal(), literal);
+
+        // Regions with 3 and 5 cells
+        let regions = Rules::<4, 2>::regions_from_literal("AAAB BBBB CCDD CCDD").unwrap();
+        assert!(!rules.set_regions(regions));
+    }
+}
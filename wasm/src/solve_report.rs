@@ -1,62 +1,177 @@
-use json::{JsonValue, object};
+use serde::{Deserialize, Serialize};
+use crate::pairs::PairKind;
+use crate::rules::ExtraHouse;
+use crate::sudoku_board::SudokuBoard;
 
+/// Observation made by the solver about the board. Serialized as an object whose "t" field names
+/// the variant in camelCase, like {"t": "tried", "num": 5, "row": 0, "col": 3}.
+/// Rows and columns are 0-based in "tried" and 1-based in the other messages
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", rename_all = "camelCase")]
 pub enum Message {
-    Found(u64),
-    Tried(u8, usize, usize),
+    Found { ms: u64 },
+    Tried { num: u8, row: usize, col: usize },
     GaveUp,
-    CanContainOnly(u8, usize, usize),
-    NumberOnlyFitsInCol(u8, usize),
-    NumberOnlyFitsInRow(u8, usize),
-    NumberOnlyFitsInBlock(u8, usize, usize),
+    CanContainOnly { num: u8, row: usize, col: usize },
+    NumberOnlyFitsInCol { num: u8, col: usize },
+    NumberOnlyFitsInRow { num: u8, row: usize },
+    NumberOnlyFitsInBlock { num: u8, row: usize, col: usize },
+    /// Same as NumberOnlyFitsInBlock, for the 1-based region of a Jigsaw Sudoku
+    NumberOnlyFitsInRegion { num: u8, region: usize },
+    /// Same as NumberOnlyFitsInBlock, for the 1-based diagonal of a Sudoku-X
+    NumberOnlyFitsInDiagonal { num: u8, diagonal: usize },
+    /// Same as NumberOnlyFitsInBlock, for the window of a Windoku at the 1-based row/col
+    NumberOnlyFitsInWindow { num: u8, row: usize, col: usize },
+    /// The remaining cells of the cage whose top left cell is at row/col must contain one of the
+    /// combinations of numbers
+    CageCombinations { sum: u16, row: usize, col: usize, combinations: Vec<Vec<u8>> },
+    /// Numbers along the thermometer whose bulb is at row/col must increase, which removes some
+    /// candidates from its cells
+    ThermometerIncreases { row: usize, col: usize },
+    /// The circle at row/col must contain the sum of its arrow, which removes some candidates from
+    /// its cells
+    ArrowSum { row: usize, col: usize },
+    /// Cells at the same distance from the ends of the palindrome starting at row/col must contain
+    /// the same number, which removes some candidates from them
+    PalindromeMirrors { row: usize, col: usize },
+    /// The numbers at row/col and row2/col2 must follow the relation of the clue between them,
+    /// which removes some candidates from them
+    PairClue { kind: PairKind, row: usize, col: usize, row2: usize, col2: usize },
+    /// The numbers between the 1 and the biggest number in the row must add up to the sum
+    RowSandwich { sum: u16, row: usize },
+    /// The numbers between the 1 and the biggest number in the column must add up to the sum
+    ColSandwich { sum: u16, col: usize },
+    /// The numbers along the diagonal starting at row/col must add up to the sum
+    LittleKiller { sum: u16, row: usize, col: usize },
+    /// Explanation given by a rule defined outside the crate, shown as it is
+    Custom { text: String },
+    /// In rows row and row2, the number only fits in columns col and col2, so it can't be anywhere
+    /// else in those columns
+    XWingInRows { num: u8, row: usize, row2: usize, col: usize, col2: usize },
+    /// Same as XWingInRows, with the number only fitting in two rows of columns col and col2
+    XWingInCols { num: u8, row: usize, row2: usize, col: usize, col2: usize },
 }
 
 impl Message {
-    pub fn into_object(self) -> JsonValue {
-        use Message::*;
+    /// Technique the solver used to make the observation, or None for guesses and the messages
+    /// that don't describe a step
+    pub fn technique(&self) -> Option<Technique> {
         match self {
-            Found(ms) => object! {
-                t: "found",
-                ms: ms
-            },
-            Tried(num, row, col) => object! {
-                t: "tried",
-                num: num,
-                row: row,
-                col: col
-            },
-            GaveUp => object! {
-                t: "gaveUp"
-            },
-            CanContainOnly(num, row, col)=>object! {
-                t: "canContainOnly",
-                num: num,
-                row: row,
-                col: col
-            },
-            NumberOnlyFitsInRow(num, row) => object! {
-                t: "numberOnlyFitsInRow",
-                num: num,
-                row: row
-            },
-            NumberOnlyFitsInCol(num, col) => object! {
-                t: "numberOnlyFitsInCol",
-                num: num,
-                col: col
-            },
-            NumberOnlyFitsInBlock(num, row, col) => object! {
-                t: "numberOnlyFitsInBlock",
-                num: num,
-                row: row,
-                col: col
-            }
+            Message::Found { .. } | Message::Tried { .. } | Message::GaveUp => None,
+            Message::CanContainOnly { .. } => Some(Technique::SoleCandidate),
+            Message::NumberOnlyFitsInCol { .. }
+            | Message::NumberOnlyFitsInRow { .. }
+            | Message::NumberOnlyFitsInBlock { .. }
+            | Message::NumberOnlyFitsInRegion { .. }
+            | Message::NumberOnlyFitsInDiagonal { .. }
+            | Message::NumberOnlyFitsInWindow { .. } => Some(Technique::UniqueCandidate),
+            Message::CageCombinations { .. }
+            | Message::ThermometerIncreases { .. }
+            | Message::ArrowSum { .. }
+            | Message::PalindromeMirrors { .. }
+            | Message::PairClue { .. }
+            | Message::RowSandwich { .. }
+            | Message::ColSandwich { .. }
+            | Message::LittleKiller { .. }
+            | Message::Custom { .. } => Some(Technique::VariantRule),
+            Message::XWingInRows { .. } | Message::XWingInCols { .. } => Some(Technique::XWing),
         }
     }
 }
 
+/// Technique used by the solver to find a number or remove candidates without guessing, from the
+/// simplest to the hardest. Serialized in camelCase, like "soleCandidate"
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Technique {
+    /// A cell can only contain one number
+    SoleCandidate,
+    /// A number only fits in one cell of a row, column, block or extra house
+    UniqueCandidate,
+    /// Candidates removed by the rules of a variant puzzle, like cages, lines and clues
+    VariantRule,
+    XWing,
+}
+
+impl Technique {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::SoleCandidate => "soleCandidate",
+            Technique::UniqueCandidate => "uniqueCandidate",
+            Technique::VariantRule => "variantRule",
+            Technique::XWing => "xWing",
+        }
+    }
+}
+
+/// A step of the solution, with the cells to highlight and the board after the step.
+/// Serialized as {"message", "highlightRow", "highlightCol", "highlightBlock", "literal"}, where
+/// the highlights are 0-based and null when absent. Steps about arbitrary groups of cells also have
+/// "highlightCells", a list of [row, col], and steps about a region of a Jigsaw Sudoku have
+/// "highlightRegion", its id, instead of "highlightBlock". Steps about a diagonal or window have
+/// "highlightHouse", like {"type": "diagonal", "value": 0}
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ReportStep<const SIZE: usize, const BLOCK_SIZE: usize> {
     pub message: Message,
     pub highlight_row: Option<u8>,
     pub highlight_col: Option<u8>,
     pub highlight_block: Option<[u8; 2]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlight_cells: Vec<[u8; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight_region: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight_house: Option<ExtraHouse>,
     pub literal: String
 }
+
+/// Everything produced by solving a board.
+/// Serialized as {"solution", "steps", "elapsedMicros"}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolveResult<const SIZE: usize, const BLOCK_SIZE: usize> {
+    pub solution: SudokuBoard<SIZE, BLOCK_SIZE>,
+    pub steps: Vec<ReportStep<SIZE, BLOCK_SIZE>>,
+    pub elapsed_micros: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solve_report::{Message, ReportStep, Technique};
+
+    #[test]
+    fn message_schema() {
+        let message = Message::Tried { num: 5, row: 0, col: 3 };
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#"{"t":"tried","num":5,"row":0,"col":3}"#);
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+
+        assert_eq!(serde_json::to_string(&Message::GaveUp).unwrap(), r#"{"t":"gaveUp"}"#);
+    }
+
+    #[test]
+    fn techniques() {
+        assert_eq!(Message::Tried { num: 5, row: 0, col: 3 }.technique(), None);
+        assert_eq!(Message::NumberOnlyFitsInWindow { num: 1, row: 2, col: 2 }.technique(), Some(Technique::UniqueCandidate));
+        assert!(Technique::SoleCandidate < Technique::XWing);
+        assert_eq!(serde_json::to_string(&Technique::XWing).unwrap(), r#""xWing""#);
+    }
+
+    #[test]
+    fn step_schema() {
+        let step = ReportStep::<9, 3> {
+            message: Message::NumberOnlyFitsInBlock { num: 1, row: 2, col: 3 },
+            highlight_row: None,
+            highlight_col: None,
+            highlight_block: Some([1, 2]),
+            highlight_cells: Vec::new(),
+            highlight_region: None,
+            highlight_house: None,
+            literal: "1 _ ".to_owned(),
+        };
+        let json = serde_json::to_string(&step).unwrap();
+        assert_eq!(json, r#"{"message":{"t":"numberOnlyFitsInBlock","num":1,"row":2,"col":3},"highlightRow":null,"highlightCol":null,"highlightBlock":[1,2],"literal":"1 _ "}"#);
+        assert_eq!(serde_json::from_str::<ReportStep<9, 3>>(&json).unwrap(), step);
+    }
+}
@@ -1,63 +1,509 @@
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::killer::Cage;
+use crate::constraint::Constraint;
+use crate::lines::Line;
 use crate::number_options::{NumberOptions};
+use crate::outside::OutsideClue;
+use crate::pairs::PairClue;
+use crate::rules::{ExtraHouse, Rules};
 use crate::util::Array2D;
 
 pub type DefaultBoard = SudokuBoard<9, 3>;
 
+/// Where the number in a cell came from. In literals, user numbers are followed by '+' and solver
+/// numbers by '*', like "5+" and "5*". Numbers without a suffix are givens.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CellOrigin {
+    /// Clue of the puzzle, which should never be changed
+    #[default]
+    Given,
+    /// Number placed by the player
+    User,
+    /// Number placed by the solver
+    Solver,
+}
+
+impl CellOrigin {
+    fn suffix(&self) -> &'static str {
+        match self {
+            CellOrigin::Given => "",
+            CellOrigin::User => "+",
+            CellOrigin::Solver => "*",
+        }
+    }
+
+    /// Split a literal cell like "5+" into its number and origin
+    fn parse_cell(text: &str) -> (Option<u8>, CellOrigin) {
+        let (text, origin) = if let Some(text) = text.strip_suffix('+') {
+            (text, CellOrigin::User)
+        } else if let Some(text) = text.strip_suffix('*') {
+            (text, CellOrigin::Solver)
+        } else {
+            (text, CellOrigin::Given)
+        };
+        (u8::from_str(text).ok(), origin)
+    }
+}
+
 /// Struct that keeps track of the numbers in the board and also what values are already used
-/// in each row/column/block
-#[derive(Clone, Eq, PartialEq)]
+/// in each row/column/block.
+/// Serialized as {"size", "blockSize", "cells"}, where "cells" is an array of rows and each cell is
+/// null or {"value", "origin"}. Jigsaw puzzles also have "regions", an array of rows with the
+/// region id of each cell, and Killer puzzles have "cages", a list of {"sum", "cells"}. Sudoku-X,
+/// Windoku, anti-knight and anti-king puzzles set "diagonals", "windows", "antiKnight" and
+/// "antiKing" to true. Thermometers, arrows and palindromes are in "lines", and Kropki dots, XV
+/// and inequality clues in "pairs", both lists of {"kind", "cells"}. "negativeKropki" and
+/// "negativeXv" say that every dot or XV clue is given. Sandwich and Little Killer clues are in
+/// "outside", and "masks" has the numbers allowed in each cell, or null for any number. "allowed"
+/// has the candidates left in each cell after eliminations, like pencil marks, in the same way
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "BoardSchema", try_from = "BoardSchema")]
 pub struct SudokuBoard<const SIZE: usize, const BLOCK_SIZE: usize> {
     pub numbers: Array2D<Option<u8>, SIZE>,
+    /// Origin of each number. Only meaningful for cells that contain a number
+    pub origins: Array2D<CellOrigin, SIZE>,
     rows: [NumberOptions<SIZE>; SIZE],
     cols: [NumberOptions<SIZE>; SIZE],
-    blocks: Array2D<NumberOptions<SIZE>, BLOCK_SIZE>,
+    /// Numbers used in each region, which are the blocks unless it's a Jigsaw Sudoku
+    blocks: [NumberOptions<SIZE>; SIZE],
+    /// Numbers that each cell is still allowed to contain, regardless of the other cells.
+    /// Used to keep eliminations that can't be deduced from the numbers alone
+    allowed: Array2D<NumberOptions<SIZE>, SIZE>,
+    rules: Arc<Rules<SIZE, BLOCK_SIZE>>,
 }
 
+/// Rule broken by a board. Serialized as {"type", "value"}, where "type" is "row", "col", "block",
+/// "region", "cage", "diagonal", "window", "knight", "king", "line", "pair", "negative",
+/// "outside", "mask" or "custom", and "value" is the index of the row/column/region/cage/diagonal/
+/// line/pair/clue/custom rule or the [row, col] of the block/window/cell
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum BoardError {
+    #[serde(rename = "row")]
     RowError(usize),
+    #[serde(rename = "col")]
     ColError(usize),
+    #[serde(rename = "block")]
     BlockError(usize, usize),
+    /// Id of the region of a Jigsaw Sudoku
+    #[serde(rename = "region")]
+    RegionError(usize),
+    /// Index of the cage
+    #[serde(rename = "cage")]
+    CageError(usize),
+    /// 0 for the main diagonal and 1 for the anti-diagonal
+    #[serde(rename = "diagonal")]
+    DiagonalError(usize),
+    /// [row, col] of the window
+    #[serde(rename = "window")]
+    WindowError(usize, usize),
+    /// [row, col] of the first cell with the same number as a cell a knight's move apart
+    #[serde(rename = "knight")]
+    KnightError(usize, usize),
+    /// [row, col] of the first cell with the same number as a cell a king's move apart
+    #[serde(rename = "king")]
+    KingError(usize, usize),
+    /// Index of the thermometer, arrow or palindrome
+    #[serde(rename = "line")]
+    LineError(usize),
+    /// Index of the Kropki dot, XV or inequality clue
+    #[serde(rename = "pair")]
+    PairError(usize),
+    /// [row, col] of the first of two adjacent cells that would need a clue that isn't given
+    #[serde(rename = "negative")]
+    NegativeError(usize, usize),
+    /// Index of the sandwich or Little Killer clue
+    #[serde(rename = "outside")]
+    OutsideError(usize),
+    /// [row, col] of a cell with a number its mask doesn't allow, like an even number in an odd cell
+    #[serde(rename = "mask")]
+    MaskError(usize, usize),
+    /// Index of the rule added with [Rules::add_constraint]
+    #[serde(rename = "custom")]
+    CustomError(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CellSchema {
+    value: u8,
+    origin: CellOrigin,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BoardSchema {
+    size: usize,
+    block_size: usize,
+    cells: Vec<Vec<Option<CellSchema>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regions: Option<Vec<Vec<usize>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cages: Vec<Cage>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    diagonals: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    windows: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    anti_knight: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    anti_king: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lines: Vec<Line>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pairs: Vec<PairClue>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    negative_kropki: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    negative_xv: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    outside: Vec<OutsideClue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    masks: Option<Vec<Vec<Option<Vec<u8>>>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed: Option<Vec<Vec<Option<Vec<u8>>>>>,
+}
+
+/// Write the numbers of each cell, or null for cells that allow every number. Return None if every
+/// cell allows every number
+fn options_to_schema<const SIZE: usize>(options: impl Fn(usize, usize) -> NumberOptions<SIZE>) -> Option<Vec<Vec<Option<Vec<u8>>>>> {
+    let grid: Vec<Vec<Option<Vec<u8>>>> = (0..SIZE)
+        .map(|row| (0..SIZE)
+            .map(|col| Some(options(row, col)).filter(|o| !o.all()).map(|o| o.as_vec()))
+            .collect())
+        .collect();
+    grid.iter().flatten().any(Option::is_some).then_some(grid)
+}
+
+/// Read the numbers of each cell written by [options_to_schema], as [row, col, numbers] for the
+/// cells that don't allow every number
+fn options_from_schema<const SIZE: usize>(grid: Vec<Vec<Option<Vec<u8>>>>, name: &str)
+                                          -> Result<Vec<(usize, usize, NumberOptions<SIZE>)>, String> {
+    if grid.len() != SIZE || grid.iter().any(|o| o.len() != SIZE) {
+        return Err(format!("{} must have {} rows and columns", name, SIZE));
+    }
+    let mut result = Vec::new();
+    for (row, cells) in grid.into_iter().enumerate() {
+        for (col, numbers) in cells.into_iter().enumerate() {
+            let Some(numbers) = numbers else { continue; };
+            let mut options = NumberOptions::default();
+            for num in numbers {
+                if num == 0 || num as usize > SIZE {
+                    return Err(format!("{} must have numbers from 1 to {}", name, SIZE));
+                }
+                options.add_number(num);
+            }
+            result.push((row, col, options));
+        }
+    }
+    Ok(result)
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> From<SudokuBoard<SIZE, BLOCK_SIZE>> for BoardSchema {
+    fn from(board: SudokuBoard<SIZE, BLOCK_SIZE>) -> Self {
+        BoardSchema {
+            size: SIZE,
+            block_size: BLOCK_SIZE,
+            cells: (0..SIZE)
+                .map(|row| (0..SIZE)
+                    .map(|col| board.get_number(row, col).map(|value| CellSchema {
+                        value,
+                        origin: board.origins[row][col],
+                    }))
+                    .collect())
+                .collect(),
+            regions: board.rules.is_jigsaw().then(|| (0..SIZE)
+                .map(|row| (0..SIZE).map(|col| board.rules.region_of(row, col)).collect())
+                .collect()),
+            cages: board.rules.cages().to_vec(),
+            diagonals: board.rules.has_diagonals(),
+            windows: board.rules.has_windows(),
+            anti_knight: board.rules.is_anti_knight(),
+            anti_king: board.rules.is_anti_king(),
+            lines: board.rules.lines().to_vec(),
+            pairs: board.rules.pairs().to_vec(),
+            negative_kropki: board.rules.is_negative_kropki(),
+            negative_xv: board.rules.is_negative_xv(),
+            outside: board.rules.outside().to_vec(),
+            masks: options_to_schema(|row, col| board.rules.mask(row, col)),
+            allowed: options_to_schema(|row, col| board.get_allowed(row, col)),
+        }
+    }
+}
+
+impl<const SIZE: usize, const BLOCK_SIZE: usize> TryFrom<BoardSchema> for SudokuBoard<SIZE, BLOCK_SIZE> {
+    type Error = String;
+
+    fn try_from(schema: BoardSchema) -> Result<Self, Self::Error> {
+        if schema.size != SIZE || schema.block_size != BLOCK_SIZE {
+            return Err(format!("Expected a board of size {}, found {}", SIZE, schema.size));
+        }
+        if schema.cells.len() != SIZE || schema.cells.iter().any(|o| o.len() != SIZE) {
+            return Err(format!("Expected {} rows of {} cells", SIZE, SIZE));
+        }
+
+        let mut board = SudokuBoard::new();
+        if let Some(regions) = schema.regions {
+            let mut region_of = [[0; SIZE]; SIZE];
+            for (row, cells) in regions.into_iter().enumerate().take(SIZE) {
+                for (col, region) in cells.into_iter().enumerate().take(SIZE) {
+                    region_of[row][col] = region;
+                }
+            }
+            if !board.set_regions(region_of) {
+                return Err(format!("Expected {} regions of {} cells", SIZE, SIZE));
+            }
+        }
+
+        if schema.diagonals {
+            board.rules_mut().add_diagonals();
+        }
+        if schema.windows {
+            board.rules_mut().add_windows();
+        }
+        board.rules_mut().set_anti_knight(schema.anti_knight);
+        board.rules_mut().set_anti_king(schema.anti_king);
+        board.rules_mut().set_negative_kropki(schema.negative_kropki);
+        board.rules_mut().set_negative_xv(schema.negative_xv);
+
+        for cage in schema.cages {
+            if !board.rules_mut().add_cage(cage) {
+                return Err("Cages must be inside the board and can't overlap".to_owned());
+            }
+        }
+
+        for line in schema.lines {
+            if !board.rules_mut().add_line(line) {
+                return Err("Lines must be inside the board and have at least 2 different cells".to_owned());
+            }
+        }
+
+        for pair in schema.pairs {
+            if !board.rules_mut().add_pair(pair) {
+                return Err("Pair clues must be between adjacent cells".to_owned());
+            }
+        }
+
+        for clue in schema.outside {
+            if !board.rules_mut().add_outside(clue) {
+                return Err("Outside clues must point into the board".to_owned());
+            }
+        }
+
+        if let Some(masks) = schema.masks {
+            for (row, col, mask) in options_from_schema(masks, "Masks")? {
+                board.rules_mut().set_mask(row, col, mask);
+            }
+        }
+        if let Some(allowed) = schema.allowed {
+            for (row, col, options) in options_from_schema(allowed, "Allowed candidates")? {
+                board.restrict(row, col, options);
+            }
+        }
+
+        for (row, cells) in schema.cells.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                if let Some(cell) = cell {
+                    if cell.value == 0 || cell.value as usize > SIZE {
+                        return Err(format!("Invalid number {} at {} {}", cell.value, row, col));
+                    }
+                    board.set_cell(Some(cell.value), cell.origin, row, col);
+                }
+            }
+        }
+        Ok(board)
+    }
 }
 
 impl<const SIZE: usize, const BLOCK_SIZE: usize> SudokuBoard<SIZE, BLOCK_SIZE> {
     pub fn new() -> Self {
         SudokuBoard {
             numbers: [[None; SIZE]; SIZE],
+            origins: [[CellOrigin::Given; SIZE]; SIZE],
             cols: [NumberOptions::default(); SIZE],
             rows: [NumberOptions::default(); SIZE],
-            blocks: [[NumberOptions::default(); BLOCK_SIZE]; BLOCK_SIZE],
+            blocks: [NumberOptions::default(); SIZE],
+            allowed: [[NumberOptions::full(); SIZE]; SIZE],
+            rules: Arc::new(Rules::new()),
+        }
+    }
+
+    pub fn with_rules(rules: Rules<SIZE, BLOCK_SIZE>) -> Self {
+        SudokuBoard {
+            rules: Arc::new(rules),
+            ..SudokuBoard::new()
+        }
+    }
+
+    pub fn rules(&self) -> &Rules<SIZE, BLOCK_SIZE> {
+        &self.rules
+    }
+
+    /// Change the rules of this board. Copies made before keep the previous rules
+    pub fn rules_mut(&mut self) -> &mut Rules<SIZE, BLOCK_SIZE> {
+        Arc::make_mut(&mut self.rules)
+    }
+
+    /// Turn the board into a Jigsaw Sudoku with the region of each cell.
+    /// Return false if the map is invalid, see [Rules::set_regions]
+    pub fn set_regions(&mut self, region_of: Array2D<usize, SIZE>) -> bool {
+        if !self.rules_mut().set_regions(region_of) {
+            return false;
         }
+
+        self.blocks = [NumberOptions::default(); SIZE];
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if let Some(num) = self.numbers[row][col] {
+                    self.blocks[self.rules.region_of(row, col)].add_number(num);
+                }
+            }
+        }
+        true
     }
 
+    /// Set a number as a given. See [SudokuBoard::set_cell] to specify another origin
     pub fn set_number(&mut self, value: Option<u8>, row: usize, col: usize) {
+        self.set_cell(value, CellOrigin::Given, row, col);
+    }
+
+    pub fn set_cell(&mut self, value: Option<u8>, origin: CellOrigin, row: usize, col: usize) {
         let prev = self.numbers[row][col];
         if let Some(val) = prev {
             self.rows[row].remove_number(val);
             self.cols[col].remove_number(val);
-            self.blocks[row / BLOCK_SIZE][col / BLOCK_SIZE].remove_number(val);
+            self.blocks[self.rules.region_of(row, col)].remove_number(val);
             self.numbers[row][col] = None;
         }
 
         if let Some(val) = value {
             self.rows[row].add_number(val);
             self.cols[col].add_number(val);
-            self.blocks[row / BLOCK_SIZE][col / BLOCK_SIZE].add_number(val);
+            self.blocks[self.rules.region_of(row, col)].add_number(val);
             self.numbers[row][col] = value;
+            self.origins[row][col] = origin;
+        }
+    }
+
+    /// Set a number placed by the player, leaving the givens untouched.
+    /// Return whether the cell could be changed
+    pub fn set_user_number(&mut self, value: Option<u8>, row: usize, col: usize) -> bool {
+        if self.is_given(row, col) {
+            return false;
         }
+        self.set_cell(value, CellOrigin::User, row, col);
+        true
+    }
+
+    /// Return the origin of the number in the cell, or None if it's empty
+    pub fn get_origin(&self, row: usize, col: usize) -> Option<CellOrigin> {
+        self.numbers[row][col].map(|_| self.origins[row][col])
+    }
+
+    pub fn is_given(&self, row: usize, col: usize) -> bool {
+        self.get_origin(row, col) == Some(CellOrigin::Given)
+    }
+
+    /// Turn every number in the board into a given. Used when a solved board becomes a new puzzle
+    pub fn mark_all_as_given(&mut self) {
+        self.origins = [[CellOrigin::Given; SIZE]; SIZE];
+    }
+
+    /// Return a copy of the board containing only the givens
+    pub fn givens(&self) -> Self {
+        let mut result = SudokuBoard::new();
+        result.rules = self.rules.clone();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if self.is_given(row, col) {
+                    result.set_number(self.numbers[row][col], row, col);
+                }
+            }
+        }
+        result
     }
 
     pub fn get_number(&self, row: usize, col: usize) -> Option<u8> {
         self.numbers[row][col]
     }
 
+    /// Whether both boards contain the same numbers, regardless of where they came from, the
+    /// candidates left and the rules
+    pub fn same_numbers(&self, other: &Self) -> bool {
+        self.numbers == other.numbers
+    }
+
     pub fn get_possible(&self, row: usize, col: usize) -> NumberOptions<SIZE> {
-        !(self.rows[row] | self.cols[col] | self.blocks[row / BLOCK_SIZE][col / BLOCK_SIZE])
+        let mut used = self.rows[row] | self.cols[col] | self.blocks[self.rules.region_of(row, col)];
+        if !self.rules.is_empty() {
+            used |= self.used_by_rules(row, col);
+        }
+        !used & self.allowed[row][col]
+    }
+
+    /// Return the numbers that the extra rules forbid in a cell because of the other cells
+    fn used_by_rules(&self, row: usize, col: usize) -> NumberOptions<SIZE> {
+        let mut result = !self.rules.mask(row, col);
+        for house in self.rules.extra_houses_of(row, col) {
+            for [r, c] in house.cells::<SIZE, BLOCK_SIZE>() {
+                if let Some(num) = self.numbers[r][c] {
+                    result.add_number(num);
+                }
+            }
+        }
+
+        for [r, c] in self.rules.knight_cells(row, col).chain(self.rules.king_cells(row, col)) {
+            if let Some(num) = self.numbers[r][c] {
+                result.add_number(num);
+            }
+        }
+
+        for [r, c] in Self::orthogonal_cells(row, col) {
+            let Some(num) = self.numbers[r][c] else { continue; };
+            for kind in self.rules.forbidden_pair_kinds([row, col], [r, c]) {
+                for candidate in 1..=SIZE as u8 {
+                    if kind.holds(candidate, num) {
+                        result.add_number(candidate);
+                    }
+                }
+            }
+        }
+
+        if let Some(cage) = self.rules.cage_of(row, col) {
+            for &[r, c] in &cage.cells {
+                if let Some(num) = self.numbers[r][c] {
+                    result.add_number(num);
+                }
+            }
+        }
+        result
+    }
+
+    /// Return the cells that share a side with a cell
+    fn orthogonal_cells(row: usize, col: usize) -> impl Iterator<Item=[usize; 2]> {
+        [[row.wrapping_sub(1), col], [row + 1, col], [row, col.wrapping_sub(1)], [row, col + 1]]
+            .into_iter()
+            .filter(|&[r, c]| r < SIZE && c < SIZE)
+    }
+
+    /// Remove from a cell every candidate that isn't in the options
+    pub fn restrict(&mut self, row: usize, col: usize, options: NumberOptions<SIZE>) {
+        self.allowed[row][col] = self.allowed[row][col] & options;
+    }
+
+    pub fn get_allowed(&self, row: usize, col: usize) -> NumberOptions<SIZE> {
+        self.allowed[row][col]
     }
 
     pub fn from_literal(literal: &str) -> Self {
-        let mut board = SudokuBoard::new();
+        Self::from_literal_with_rules(literal, Rules::new())
+    }
+
+    pub fn from_literal_with_rules(literal: &str, rules: Rules<SIZE, BLOCK_SIZE>) -> Self {
+        let mut board = SudokuBoard::with_rules(rules);
 
         literal
             .replace('\n', " ")
@@ -65,20 +511,22 @@ impl<const SIZE: usize, const BLOCK_SIZE: usize> SudokuBoard<SIZE, BLOCK_SIZE> {
             .filter(|o| !o.is_empty())
             .enumerate()
             .for_each(|(index, o)| {
-                board.set_number(u8::from_str(o).ok(), index / SIZE, index % SIZE)
+                let (value, origin) = CellOrigin::parse_cell(o);
+                board.set_cell(value, origin, index / SIZE, index % SIZE)
             });
 
         board
     }
 
-    /// Return a more compact representation of the board, in the format "1 2 3 _ _ 6 7 8 _"
+    /// Return a more compact representation of the board, in the format "1 2 3 _ _ 6+ 7* 8 _"
     /// without newlines
     pub fn to_literal(&self) -> String {
         let mut result = String::new();
-        for row in &self.numbers {
-            for num in row {
+        for (numbers, origins) in self.numbers.iter().zip(self.origins.iter()) {
+            for (num, origin) in numbers.iter().zip(origins.iter()) {
                 if num.is_some() {
                     result += &num.unwrap().to_string();
+                    result += origin.suffix();
                 } else {
                     result += "_";
                 }
@@ -90,16 +538,22 @@ impl<const SIZE: usize, const BLOCK_SIZE: usize> SudokuBoard<SIZE, BLOCK_SIZE> {
     }
 
     pub fn from_literal_checked(literal: &str) -> Result<Self, BoardError> {
-        let mut board = SudokuBoard::new();
+        Self::from_literal_checked_with_rules(literal, Rules::new())
+    }
+
+    /// Same as [SudokuBoard::from_literal_checked], but for variant puzzles. Only rows, columns
+    /// and regions are checked while reading, and the other rules after all the numbers are read
+    pub fn from_literal_checked_with_rules(literal: &str, rules: Rules<SIZE, BLOCK_SIZE>) -> Result<Self, BoardError> {
+        let mut board = SudokuBoard::with_rules(rules);
 
-        for (index, number) in literal
+        for (index, number, origin) in literal
             .replace('\n', " ")
             .split(' ')
             .filter(|o| !o.is_empty())
-            .map(u8::from_str)
+            .map(CellOrigin::parse_cell)
             .enumerate()
-            .filter(|(_, o)| o.is_ok())
-            .map(|(index, o)| (index, o.unwrap())) {
+            .filter(|(_, (o, _))| o.is_some())
+            .map(|(index, (o, origin))| (index, o.unwrap(), origin)) {
             let row = index / SIZE;
             let col = index % SIZE;
 
@@ -110,16 +564,168 @@ impl<const SIZE: usize, const BLOCK_SIZE: usize> SudokuBoard<SIZE, BLOCK_SIZE> {
                 return Err(BoardError::ColError(col));
             }
 
-            let block_row = row / BLOCK_SIZE;
-            let block_col = col / BLOCK_SIZE;
-            if board.blocks[block_row][block_col].has_number(number) {
-                return Err(BoardError::BlockError(block_row, block_col));
+            let region = board.rules.region_of(row, col);
+            if board.blocks[region].has_number(number) {
+                return Err(board.region_error(region));
             }
 
-            board.set_number(Some(number), row, col);
+            board.set_cell(Some(number), origin, row, col);
         }
 
-        Ok(board)
+        match board.find_rules_error() {
+            Some(error) => Err(error),
+            None => Ok(board),
+        }
+    }
+
+    /// Search for a rule broken by the numbers in the board
+    pub fn find_error(&self) -> Option<BoardError> {
+        let mut rows = [NumberOptions::<SIZE>::default(); SIZE];
+        let mut cols = [NumberOptions::<SIZE>::default(); SIZE];
+        let mut blocks = [NumberOptions::<SIZE>::default(); SIZE];
+
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let Some(number) = self.numbers[row][col] else { continue; };
+
+                if rows[row].has_number(number) {
+                    return Some(BoardError::RowError(row));
+                }
+                if cols[col].has_number(number) {
+                    return Some(BoardError::ColError(col));
+                }
+
+                let region = self.rules.region_of(row, col);
+                if blocks[region].has_number(number) {
+                    return Some(self.region_error(region));
+                }
+
+                rows[row].add_number(number);
+                cols[col].add_number(number);
+                blocks[region].add_number(number);
+            }
+        }
+
+        self.find_rules_error()
+    }
+
+    fn region_error(&self, region: usize) -> BoardError {
+        if self.rules.is_jigsaw() {
+            BoardError::RegionError(region)
+        } else {
+            BoardError::BlockError(region / BLOCK_SIZE, region % BLOCK_SIZE)
+        }
+    }
+
+    /// Whether the numbers on the board break a constraint
+    fn breaks(&self, constraint: &dyn Constraint<SIZE>) -> bool {
+        let numbers: Vec<Option<u8>> = constraint.cells().iter().map(|&[row, col]| self.numbers[row][col]).collect();
+        constraint.is_broken(&numbers)
+    }
+
+    /// Search for an extra rule broken by the numbers in the board
+    fn find_rules_error(&self) -> Option<BoardError> {
+        for &house in self.rules.extra_houses() {
+            let mut used = NumberOptions::<SIZE>::default();
+            for [row, col] in house.cells::<SIZE, BLOCK_SIZE>() {
+                let Some(num) = self.numbers[row][col] else { continue; };
+                if used.has_number(num) {
+                    return Some(match house {
+                        ExtraHouse::Diagonal(index) => BoardError::DiagonalError(index),
+                        ExtraHouse::Window(row, col) => BoardError::WindowError(row, col),
+                    });
+                }
+                used.add_number(num);
+            }
+        }
+
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let Some(num) = self.numbers[row][col] else { continue; };
+                if self.rules.knight_cells(row, col).any(|[r, c]| self.numbers[r][c] == Some(num)) {
+                    return Some(BoardError::KnightError(row, col));
+                }
+                if self.rules.king_cells(row, col).any(|[r, c]| self.numbers[r][c] == Some(num)) {
+                    return Some(BoardError::KingError(row, col));
+                }
+            }
+        }
+
+        for (index, line) in self.rules.lines().iter().enumerate() {
+            if self.breaks(line) {
+                return Some(BoardError::LineError(index));
+            }
+        }
+
+        for (index, pair) in self.rules.pairs().iter().enumerate() {
+            if self.breaks(pair) {
+                return Some(BoardError::PairError(index));
+            }
+        }
+
+        if self.rules.has_masks() {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    if self.numbers[row][col].is_some_and(|num| !self.rules.mask(row, col).has_number(num)) {
+                        return Some(BoardError::MaskError(row, col));
+                    }
+                }
+            }
+        }
+
+        for (index, clue) in self.rules.outside().iter().enumerate() {
+            if self.breaks(clue) {
+                return Some(BoardError::OutsideError(index));
+            }
+        }
+
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let Some(num) = self.numbers[row][col] else { continue; };
+                // Only the cells below and to the right, so each pair is checked once
+                for [r, c] in [[row + 1, col], [row, col + 1]] {
+                    let Some(other) = self.numbers.get(r).and_then(|o| o.get(c)).copied().flatten() else { continue; };
+                    if self.rules.forbidden_pair_kinds([row, col], [r, c]).iter().any(|kind| kind.holds(num, other)) {
+                        return Some(BoardError::NegativeError(row, col));
+                    }
+                }
+            }
+        }
+
+        for (index, cage) in self.rules.cages().iter().enumerate() {
+            let mut used = NumberOptions::<SIZE>::default();
+            let mut sum = 0;
+            let mut full = true;
+
+            for &[row, col] in &cage.cells {
+                match self.numbers[row][col] {
+                    Some(num) if used.has_number(num) => return Some(BoardError::CageError(index)),
+                    Some(num) => {
+                        used.add_number(num);
+                        sum += num as u16;
+                    }
+                    None => full = false,
+                }
+            }
+
+            if sum > cage.sum || (full && sum != cage.sum) {
+                return Some(BoardError::CageError(index));
+            }
+        }
+
+        for (index, constraint) in self.rules.custom_constraints().iter().enumerate() {
+            if self.breaks(constraint.as_ref()) {
+                return Some(BoardError::CustomError(index));
+            }
+        }
+        None
+    }
+
+    /// Whether a full board also follows the extra rules. Numbers placed according to
+    /// [SudokuBoard::get_possible] already follow the classic ones
+    #[inline]
+    pub fn satisfies_rules(&self) -> bool {
+        self.rules.is_empty() || self.find_rules_error().is_none()
     }
 
     #[inline]
@@ -156,6 +762,12 @@ impl<const SIZE: usize, const BLOCK_SIZE: usize> SudokuBoard<SIZE, BLOCK_SIZE> {
     }
 }
 
+impl<const SIZE: usize, const BLOCK_SIZE: usize> Default for SudokuBoard<SIZE, BLOCK_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<const SIZE: usize, const BLOCK_SIZE: usize> Debug for SudokuBoard<SIZE, BLOCK_SIZE> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.board_to_string())?;
@@ -165,7 +777,13 @@ impl<const SIZE: usize, const BLOCK_SIZE: usize> Debug for SudokuBoard<SIZE, BLO
 
 #[cfg(test)]
 mod tests {
-    use crate::sudoku_board::{DefaultBoard};
+    use crate::killer::Cage;
+    use crate::lines::Line;
+    use crate::number_options::NumberOptions;
+    use crate::outside::{Direction, OutsideClue};
+    use crate::pairs::{PairClue, PairKind};
+    use crate::rules::Rules;
+    use crate::sudoku_board::{BoardError, CellOrigin, DefaultBoard, SudokuBoard};
 
     #[test]
     fn empty_board() {
@@ -229,4 +847,221 @@ mod tests {
             [None; 9], [None; 9], [None; 9],
         ]);
     }
+
+    #[test]
+    fn thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DefaultBoard>();
+    }
+
+    #[test]
+    fn literal_origins() {
+        let literal = "1 2+ 3* _ _ _ _ _ _ ".to_owned() + &"_ ".repeat(72);
+        let mut board = DefaultBoard::from_literal(&literal);
+
+        assert_eq!(board.get_origin(0, 0), Some(CellOrigin::Given));
+        assert_eq!(board.get_origin(0, 1), Some(CellOrigin::User));
+        assert_eq!(board.get_origin(0, 2), Some(CellOrigin::Solver));
+        assert_eq!(board.get_origin(0, 3), None);
+        assert_eq!(board.to_literal(), literal);
+
+        assert!(!board.set_user_number(Some(4), 0, 0));
+        assert!(board.set_user_number(Some(4), 0, 3));
+        assert_eq!(board.get_origin(0, 3), Some(CellOrigin::User));
+
+        // The same numbers typed by the player
+        let typed = DefaultBoard::from_literal(&("1+ 2+ 3+ 4+ ".to_owned() + &"_ ".repeat(77)));
+        assert!(typed.same_numbers(&board) && !typed.is_given(0, 0));
+    }
+
+    #[test]
+    fn board_schema() {
+        let literal = "1 2+ 3* ".to_owned() + &"_ ".repeat(13);
+        let board = SudokuBoard::<4, 2>::from_literal(&literal);
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.starts_with(r#"{"size":4,"blockSize":2,"cells":[[{"value":1,"origin":"given"},{"value":2,"origin":"user"},{"value":3,"origin":"solver"},null]"#));
+
+        let parsed: SudokuBoard<4, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_literal(), literal);
+        assert!(serde_json::from_str::<DefaultBoard>(&json).is_err());
+        assert!(!json.contains("allowed"));
+    }
+
+    #[test]
+    fn allowed_schema() {
+        let mut board = SudokuBoard::<4, 2>::new();
+        board.restrict(0, 1, NumberOptions::single(2) | NumberOptions::single(4));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""allowed":[[null,[2,4],null,null],[null,null,null,null],"#));
+        let parsed: SudokuBoard<4, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.get_allowed(0, 1).as_vec(), vec![2, 4]);
+        assert!(parsed.get_allowed(0, 0).all());
+        assert!(serde_json::from_str::<SudokuBoard<4, 2>>(&json.replace("[2,4]", "[0]")).is_err());
+    }
+
+    #[test]
+    fn cage_errors() {
+        let mut board = DefaultBoard::new();
+        board.rules_mut().add_cage(Cage::new(4, vec![[0, 0], [4, 4]]));
+        board.set_number(Some(1), 0, 0);
+        assert_eq!(board.find_error(), None);
+        assert!(!board.get_possible(4, 4).has_number(1));
+
+        board.set_number(Some(1), 4, 4);
+        assert_eq!(board.find_error(), Some(BoardError::CageError(0)));
+
+        board.set_number(Some(2), 4, 4);
+        assert_eq!(board.find_error(), Some(BoardError::CageError(0)));
+
+        board.set_number(Some(3), 4, 4);
+        assert_eq!(board.find_error(), None);
+    }
+
+    #[test]
+    fn region_errors() {
+        let regions = Rules::<4, 2>::regions_from_literal("AABB AABB CCDD CDDC").unwrap();
+        let mut rules = Rules::new();
+        assert!(rules.set_regions(regions));
+
+        // The 4 in the last cell is in the same region as the one in the first cell of the third row
+        let result = SudokuBoard::<4, 2>::from_literal_checked_with_rules("_ _ _ _ _ _ _ _ 4 _ _ _ _ _ _ 4", rules.clone());
+        assert_eq!(result.err(), Some(BoardError::RegionError(2)));
+
+        let board = SudokuBoard::<4, 2>::from_literal_checked_with_rules("_ _ _ _ _ _ _ _ _ _ 4 _ _ _ _ 4", rules).ok().unwrap();
+        assert_eq!(board.find_error(), None);
+        assert!(!board.get_possible(3, 1).has_number(4));
+    }
+
+    #[test]
+    fn extra_house_errors() {
+        let mut rules = Rules::<4, 2>::new();
+        rules.add_diagonals();
+
+        // The 1s in the first and last cells are on the main diagonal
+        let result = SudokuBoard::<4, 2>::from_literal_checked_with_rules("1 _ _ _ _ _ _ _ _ _ _ _ _ _ _ 1", rules.clone());
+        assert_eq!(result.err(), Some(BoardError::DiagonalError(0)));
+
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("1 _ _ _ _ _ _ _ _ _ _ _ _ _ _ _", rules);
+        assert!(!board.get_possible(2, 2).has_number(1));
+        assert!(board.get_possible(2, 1).has_number(1));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""diagonals":true"#));
+        let parsed: SudokuBoard<4, 2> = serde_json::from_str(&json).unwrap();
+        assert!(parsed.rules().has_diagonals() && !parsed.rules().has_windows());
+
+        let mut rules = Rules::<9, 3>::new();
+        rules.add_windows();
+        let mut board = SudokuBoard::<9, 3>::with_rules(rules);
+        board.set_number(Some(5), 1, 1);
+        board.set_number(Some(5), 3, 3);
+        assert_eq!(board.find_error(), Some(BoardError::WindowError(0, 0)));
+    }
+
+    #[test]
+    fn chess_move_errors() {
+        let mut rules = Rules::<9, 3>::new();
+        rules.set_anti_knight(true);
+        let mut board = SudokuBoard::<9, 3>::with_rules(rules);
+        board.set_number(Some(3), 0, 0);
+        assert!(!board.get_possible(2, 1).has_number(3));
+        assert!(board.get_possible(3, 3).has_number(3));
+
+        board.set_number(Some(3), 1, 5);
+        board.set_number(Some(3), 3, 4);
+        assert_eq!(board.find_error(), Some(BoardError::KnightError(1, 5)));
+
+        let mut rules = Rules::<9, 3>::new();
+        rules.set_anti_king(true);
+        let mut board = SudokuBoard::<9, 3>::with_rules(rules);
+        board.set_number(Some(7), 2, 2);
+        board.set_number(Some(7), 3, 3);
+        assert_eq!(board.find_error(), Some(BoardError::KingError(2, 2)));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""antiKing":true"#) && !json.contains("antiKnight"));
+    }
+
+    #[test]
+    fn line_errors() {
+        let mut rules = Rules::<9, 3>::new();
+        rules.add_line(Line::thermometer(vec![[0, 0], [1, 1], [2, 2]]));
+        rules.add_line(Line::arrow(vec![[4, 4], [4, 5], [4, 6]]));
+
+        let mut board = SudokuBoard::<9, 3>::with_rules(rules);
+        board.set_number(Some(5), 0, 0);
+        board.set_number(Some(6), 2, 2);
+        assert_eq!(board.find_error(), Some(BoardError::LineError(0)));
+
+        board.set_number(Some(7), 2, 2);
+        board.set_number(Some(3), 4, 4);
+        board.set_number(Some(4), 4, 5);
+        assert_eq!(board.find_error(), Some(BoardError::LineError(1)));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""lines":[{"kind":"thermometer","cells":[[0,0],[1,1],[2,2]]}"#));
+        let parsed: SudokuBoard<9, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.rules().lines(), board.rules().lines());
+    }
+
+    #[test]
+    fn pair_errors() {
+        let mut rules = Rules::<9, 3>::new();
+        rules.add_pair(PairClue::new(PairKind::X, [0, 0], [0, 1]));
+        rules.set_negative_kropki(true);
+
+        let mut board = SudokuBoard::<9, 3>::with_rules(rules);
+        board.set_number(Some(4), 0, 0);
+        assert_eq!(board.get_possible(1, 0).as_vec(), vec![1, 6, 7, 9]);
+        board.set_number(Some(5), 0, 1);
+        assert_eq!(board.find_error(), Some(BoardError::PairError(0)));
+
+        // 4 and 6 follow the X, but the cell below 4 can't be 3 without a white dot
+        board.set_number(Some(6), 0, 1);
+        assert_eq!(board.find_error(), None);
+        board.set_number(Some(3), 1, 0);
+        assert_eq!(board.find_error(), Some(BoardError::NegativeError(0, 0)));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""pairs":[{"kind":"x","cells":[[0,0],[0,1]]}],"negativeKropki":true"#));
+    }
+
+    #[test]
+    fn outside_errors() {
+        let mut rules = Rules::<4, 2>::new();
+        rules.add_outside(OutsideClue::RowSandwich { row: 0, sum: 5 });
+        rules.add_outside(OutsideClue::LittleKiller { row: 0, col: 1, direction: Direction::DownRight, sum: 5 });
+
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("1 2 3 4 _ _ _ _ _ _ _ _ _ _ _ _", rules.clone());
+        assert_eq!(board.find_error(), None);
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("1 3 2 4 _ _ 3 _ _ _ _ _ _ _ _ _", rules.clone());
+        assert_eq!(board.find_error(), Some(BoardError::OutsideError(1)));
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("1 4 _ _ _ _ _ _ _ _ _ _ _ _ _ _", rules);
+        assert_eq!(board.find_error(), Some(BoardError::OutsideError(0)));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""outside":[{"type":"rowSandwich","row":0,"sum":5},"#));
+    }
+
+    #[test]
+    fn mask_errors() {
+        let mut rules = Rules::<4, 2>::new();
+        rules.set_mask(0, 0, NumberOptions::odd());
+        rules.set_mask(1, 1, NumberOptions::even());
+
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("_ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _", rules.clone());
+        assert_eq!(board.get_possible(0, 0).as_vec(), vec![1, 3]);
+        assert_eq!(board.get_possible(1, 1).as_vec(), vec![2, 4]);
+        assert_eq!(board.find_error(), None);
+        let board = SudokuBoard::<4, 2>::from_literal_with_rules("2 _ _ _ _ _ _ _ _ _ _ _ _ _ _ _", rules);
+        assert_eq!(board.find_error(), Some(BoardError::MaskError(0, 0)));
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(json.contains(r#""masks":[[[1,3],null,null,null],[null,[2,4],null,null],"#));
+        let read: SudokuBoard<4, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.rules().mask(1, 1), NumberOptions::even());
+        assert!(serde_json::from_str::<SudokuBoard<4, 2>>(&json.replace("[2,4]", "[2,5]")).is_err());
+    }
 }
@@ -0,0 +1,203 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::sudoku_board::SudokuBoard;
+
+// Transformations that turn a valid board into another valid board, with the same number of
+// solutions and the same difficulty. They only move the numbers, so they return None for boards
+// with the rules of a variant, like cages or Jigsaw regions, which would need moving too
+
+/// Whether the board only has the rows, columns and rectangular blocks of a classic Sudoku
+fn is_classic<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> bool {
+    board.rules().is_empty() && !board.rules().is_jigsaw()
+}
+
+/// Build a board whose cell at [row, col] has the number and origin of the cell at source(row, col)
+fn map_cells<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>,
+                                                         source: impl Fn(usize, usize) -> [usize; 2]) -> SudokuBoard<SIZE, BLOCK_SIZE> {
+    let mut result = SudokuBoard::with_rules(board.rules().clone());
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            let [r, c] = source(row, col);
+            if let Some(origin) = board.get_origin(r, c) {
+                result.set_cell(board.get_number(r, c), origin, row, col);
+            }
+        }
+    }
+    result
+}
+
+/// Replace each number n by digits[n - 1]. Return None if digits isn't a permutation of the numbers
+pub fn relabel<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, digits: &[u8; SIZE]) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    let mut sorted = *digits;
+    sorted.sort();
+    if !is_classic(board) || sorted.iter().enumerate().any(|(index, &o)| o as usize != index + 1) {
+        return None;
+    }
+
+    let mut result = SudokuBoard::with_rules(board.rules().clone());
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            if let (Some(num), Some(origin)) = (board.get_number(row, col), board.get_origin(row, col)) {
+                result.set_cell(Some(digits[num as usize - 1]), origin, row, col);
+            }
+        }
+    }
+    Some(result)
+}
+
+/// Swap two rows of the same band. Return None if they are in different bands
+pub fn swap_rows<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, a: usize, b: usize) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) || a / BLOCK_SIZE != b / BLOCK_SIZE || a >= SIZE || b >= SIZE {
+        return None;
+    }
+    Some(map_cells(board, |row, col| [swapped(row, a, b), col]))
+}
+
+/// Swap two columns of the same stack. Return None if they are in different stacks
+pub fn swap_cols<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, a: usize, b: usize) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) || a / BLOCK_SIZE != b / BLOCK_SIZE || a >= SIZE || b >= SIZE {
+        return None;
+    }
+    Some(map_cells(board, |row, col| [row, swapped(col, a, b)]))
+}
+
+/// Swap two bands, which are the rows of blocks, by their index. Return None if there's no such band
+pub fn swap_bands<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, a: usize, b: usize) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) || a >= BLOCK_SIZE || b >= BLOCK_SIZE {
+        return None;
+    }
+    Some(map_cells(board, |row, col| [swapped(row / BLOCK_SIZE, a, b) * BLOCK_SIZE + row % BLOCK_SIZE, col]))
+}
+
+/// Swap two stacks, which are the columns of blocks, by their index. Return None if there's no
+/// such stack
+pub fn swap_stacks<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, a: usize, b: usize) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) || a >= BLOCK_SIZE || b >= BLOCK_SIZE {
+        return None;
+    }
+    Some(map_cells(board, |row, col| [row, swapped(col / BLOCK_SIZE, a, b) * BLOCK_SIZE + col % BLOCK_SIZE]))
+}
+
+/// Mirror the board across its main diagonal, so rows become columns
+pub fn transpose<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) {
+        return None;
+    }
+    Some(map_cells(board, |row, col| [col, row]))
+}
+
+/// Rotate the board clockwise by a number of quarter turns
+pub fn rotate<const SIZE: usize, const BLOCK_SIZE: usize>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, quarter_turns: usize) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) {
+        return None;
+    }
+    let last = SIZE - 1;
+    Some(match quarter_turns % 4 {
+        0 => board.clone(),
+        1 => map_cells(board, |row, col| [last - col, row]),
+        2 => map_cells(board, |row, col| [last - row, last - col]),
+        _ => map_cells(board, |row, col| [col, last - row]),
+    })
+}
+
+/// Apply a random combination of every transformation. Each one is drawn from the same
+/// generator, so a seeded generator always gives the same result
+pub fn random_transform<const SIZE: usize, const BLOCK_SIZE: usize, R: Rng>(board: &SudokuBoard<SIZE, BLOCK_SIZE>, rand: &mut R) -> Option<SudokuBoard<SIZE, BLOCK_SIZE>> {
+    if !is_classic(board) {
+        return None;
+    }
+    let mut digits = [0; SIZE];
+    for (index, digit) in digits.iter_mut().enumerate() {
+        *digit = index as u8 + 1;
+    }
+    digits.shuffle(rand);
+
+    // New position of each row and column, built from shuffled bands and rows inside them
+    let shuffled = |rand: &mut R| {
+        let mut groups: Vec<usize> = (0..BLOCK_SIZE).collect();
+        groups.shuffle(rand);
+        let mut result = Vec::with_capacity(SIZE);
+        for group in groups {
+            let mut lines: Vec<usize> = (group * BLOCK_SIZE..(group + 1) * BLOCK_SIZE).collect();
+            lines.shuffle(rand);
+            result.extend(lines);
+        }
+        result
+    };
+    let rows = shuffled(rand);
+    let cols = shuffled(rand);
+    let quarter_turns = rand.gen_range(0..4_u32) as usize;
+
+    let result = relabel(board, &digits)?;
+    let result = map_cells(&result, |row, col| [rows[row], cols[col]]);
+    rotate(&result, quarter_turns)
+}
+
+fn swapped(value: usize, a: usize, b: usize) -> usize {
+    if value == a { b } else if value == b { a } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use crate::sudoku_board::DefaultBoard;
+    use crate::sudoku_examples::EASY_LITERALS;
+    use crate::sudoku_solver::SudokuSolver;
+    use crate::transform::{random_transform, relabel, rotate, swap_bands, swap_cols, swap_rows, swap_stacks, transpose};
+
+    #[test]
+    fn transformations() {
+        let solution = SudokuSolver::new(0).solve(&DefaultBoard::from_literal(EASY_LITERALS[0])).unwrap();
+        let transformed = [
+            relabel(&solution, &[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap(),
+            swap_rows(&solution, 0, 2).unwrap(),
+            swap_cols(&solution, 4, 5).unwrap(),
+            swap_bands(&solution, 0, 2).unwrap(),
+            swap_stacks(&solution, 1, 2).unwrap(),
+            transpose(&solution).unwrap(),
+            rotate(&solution, 1).unwrap(),
+        ];
+        for board in transformed {
+            assert!(board.is_full() && board.find_error().is_none());
+            assert!(!board.same_numbers(&solution));
+        }
+
+        // The solution starts with 8 6 9 in the first row and 4 5 3 in the second
+        assert_eq!(relabel(&solution, &[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap().get_number(0, 0), Some(2));
+        assert_eq!(transpose(&solution).unwrap().get_number(1, 0), Some(6));
+        assert_eq!(rotate(&solution, 1).unwrap().get_number(0, 8), Some(8));
+        assert!(rotate(&rotate(&solution, 3).unwrap(), 1).unwrap().same_numbers(&solution));
+        assert_eq!(swap_bands(&solution, 0, 1).unwrap().get_number(3, 0), Some(8));
+
+        assert!(swap_rows(&solution, 2, 3).is_none());
+        assert!(swap_cols(&solution, 0, 8).is_none());
+        assert!(relabel(&solution, &[1, 1, 2, 3, 4, 5, 6, 7, 8]).is_none());
+        assert!(swap_bands(&solution, 0, 3).is_none());
+        assert!(swap_stacks(&solution, 3, 1).is_none());
+    }
+
+    #[test]
+    fn variant_rules() {
+        let mut board = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        board.rules_mut().set_anti_knight(true);
+        assert!(relabel(&board, &[9, 8, 7, 6, 5, 4, 3, 2, 1]).is_none());
+        assert!(swap_rows(&board, 0, 1).is_none());
+        assert!(transpose(&board).is_none());
+        assert!(random_transform(&board, &mut ChaCha8Rng::seed_from_u64(6)).is_none());
+    }
+
+    #[test]
+    fn random_transformation() {
+        let mut solver = SudokuSolver::new(0);
+        let puzzle = DefaultBoard::from_literal(EASY_LITERALS[0]);
+        let solution = solver.solve(&puzzle).unwrap();
+
+        // Transforming the puzzle and the solution the same way keeps them matching
+        let transformed = random_transform(&puzzle, &mut ChaCha8Rng::seed_from_u64(6)).unwrap();
+        let transformed_solution = random_transform(&solution, &mut ChaCha8Rng::seed_from_u64(6)).unwrap();
+        assert!(!transformed.same_numbers(&puzzle));
+        assert!(solver.solve(&transformed).unwrap().same_numbers(&transformed_solution));
+        assert_eq!(solver.count_solutions(&transformed, 2), 1);
+    }
+}
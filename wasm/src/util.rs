@@ -1 +1,12 @@
-pub type Array2D<T, const TSIZE: usize> = [[T; TSIZE]; TSIZE];
\ No newline at end of file
+pub type Array2D<T, const TSIZE: usize> = [[T; TSIZE]; TSIZE];
+
+/// Read a cell in the format "r1c2", where the row and column are 1-based, as a 0-based [row, col]
+pub fn cell_from_literal(literal: &str) -> Option<[usize; 2]> {
+    let (row, col) = literal.strip_prefix(['r', 'R'])?.split_once(['c', 'C'])?;
+    Some([row.parse::<usize>().ok()?.checked_sub(1)?, col.parse::<usize>().ok()?.checked_sub(1)?])
+}
+
+/// Write a 0-based [row, col] in the format "r1c2"
+pub fn cell_to_literal([row, col]: [usize; 2]) -> String {
+    format!("r{}c{}", row + 1, col + 1)
+}